@@ -0,0 +1,180 @@
+use crate::parse::{self, ErrorKind, ParseError};
+use std::fmt;
+
+const WORD: usize = u64::BITS as usize;
+
+/// A fixed width unsigned number of any width, packed into 64 bit words.
+/// Bits are indexed the way they are written, 0 being the most significant.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bits {
+    width: usize,
+    // least significant word first
+    words: Vec<u64>,
+}
+
+impl Bits {
+    pub fn zeros(width: usize) -> Self {
+        Bits {
+            width,
+            words: vec![0; width.div_ceil(WORD)],
+        }
+    }
+
+    /// Parses a string of `0` and `1`, e.g. `10110`.
+    pub fn parse(s: &str) -> parse::Result<Self> {
+        if s.is_empty() {
+            return Err(ParseError::new(1, ErrorKind::Empty));
+        }
+        let mut bits = Bits::zeros(s.len());
+        for (i, c) in s.chars().enumerate() {
+            match c {
+                '0' => {}
+                '1' => bits.set(i, true),
+                _ => return Err(ParseError::new(i + 1, ErrorKind::InvalidDigit(c))),
+            }
+        }
+        Ok(bits)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    // word and shift of the bit at `index`
+    fn locate(&self, index: usize) -> (usize, usize) {
+        assert!(index < self.width, "bit {} of {}", index, self.width);
+        let pos = self.width - 1 - index;
+        (pos / WORD, pos % WORD)
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        let (word, shift) = self.locate(index);
+        self.words[word] >> shift & 1 == 1
+    }
+
+    pub fn set(&mut self, index: usize, val: bool) {
+        let (word, shift) = self.locate(index);
+        if val {
+            self.words[word] |= 1 << shift;
+        } else {
+            self.words[word] &= !(1 << shift);
+        }
+    }
+
+    /// The bits from the most significant one down.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.width).map(|i| self.get(i))
+    }
+
+    /// Every bit flipped, keeping the width.
+    pub fn not(&self) -> Bits {
+        self.iter().map(|b| !b).collect()
+    }
+
+    /// The full product, as wide as both factors together.
+    pub fn mul(&self, other: &Bits) -> Bits {
+        let mut words = vec![0u64; self.words.len() + other.words.len()];
+        for (i, &a) in self.words.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, &b) in other.words.iter().enumerate() {
+                let sum = words[i + j] as u128 + a as u128 * b as u128 + carry;
+                words[i + j] = sum as u64;
+                carry = sum >> WORD;
+            }
+            words[i + other.words.len()] = carry as u64;
+        }
+        let mut result = Bits::zeros(self.width + other.width);
+        // the words beyond the width are zero, the product fits
+        let len = result.words.len();
+        result.words.copy_from_slice(&words[..len]);
+        result
+    }
+
+    /// The value in decimal.
+    pub fn to_decimal(&self) -> String {
+        // peel off 19 decimal digits at a time, the most a u64 holds
+        const CHUNK: u128 = 10u128.pow(19);
+        let mut words = self.words.clone();
+        let mut chunks = vec![];
+        while words.iter().any(|&w| w != 0) {
+            let mut rem = 0u128;
+            for word in words.iter_mut().rev() {
+                let cur = rem << WORD | *word as u128;
+                *word = (cur / CHUNK) as u64;
+                rem = cur % CHUNK;
+            }
+            chunks.push(rem);
+        }
+        match chunks.split_last() {
+            None => "0".to_string(),
+            Some((first, rest)) => {
+                let mut out = first.to_string();
+                for chunk in rest.iter().rev() {
+                    out.push_str(&format!("{:019}", chunk));
+                }
+                out
+            }
+        }
+    }
+}
+
+impl FromIterator<bool> for Bits {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let bits: Vec<bool> = iter.into_iter().collect();
+        let mut result = Bits::zeros(bits.len());
+        for (i, &bit) in bits.iter().enumerate() {
+            result.set(i, bit);
+        }
+        result
+    }
+}
+
+impl fmt::Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for bit in self.iter() {
+            write!(f, "{}", if bit { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn narrow_values() {
+        let bits = Bits::parse("10110").unwrap();
+        assert_eq!("22", bits.to_decimal());
+        assert_eq!("9", bits.not().to_decimal());
+        assert_eq!("01001", bits.not().to_string());
+        assert_eq!("198", bits.mul(&Bits::parse("1001").unwrap()).to_decimal());
+        assert_eq!("0", Bits::zeros(3).to_decimal());
+        assert_eq!(3, Bits::parse("10x").unwrap_err().column);
+        assert!(Bits::parse("").is_err());
+    }
+
+    #[test]
+    fn wide_values() {
+        // 2^100 - 1 and 2^100 + 1, their product 2^200 - 1
+        let ones = Bits::parse(&"1".repeat(100)).unwrap();
+        let mut edges = Bits::zeros(101);
+        edges.set(0, true);
+        edges.set(100, true);
+        assert_eq!("1267650600228229401496703205375", ones.to_decimal());
+        assert_eq!("1267650600228229401496703205377", edges.to_decimal());
+        assert_eq!(
+            "1606938044258990275541962092341162602522202993782792835301375",
+            ones.mul(&edges).to_decimal()
+        );
+        assert_eq!("0", ones.not().to_decimal());
+        assert_eq!(100, ones.not().width());
+
+        let max = Bits::parse(&"1".repeat(64)).unwrap();
+        assert_eq!(u64::MAX.to_string(), max.to_decimal());
+        assert_eq!(
+            (u64::MAX as u128 * u64::MAX as u128).to_string(),
+            max.mul(&max).to_decimal()
+        );
+    }
+}
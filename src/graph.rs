@@ -0,0 +1,65 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// Cheapest path from `start` to the first node accepted by `is_goal`.
+/// `neighbours` yields each successor with the cost of stepping onto it.
+/// Returns the total cost and the nodes along the path, `start` included.
+pub fn dijkstra<N, FN, IN, FG>(start: N, mut neighbours: FN, is_goal: FG) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash + Ord,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, u64)>,
+    FG: Fn(&N) -> bool,
+{
+    let mut cost = HashMap::from([(start.clone(), 0)]);
+    let mut prev: HashMap<N, N> = HashMap::new();
+    let mut heap = BinaryHeap::from([Reverse((0, start))]);
+
+    while let Some(Reverse((curr_cost, node))) = heap.pop() {
+        if is_goal(&node) {
+            let mut path = vec![node.clone()];
+            let mut curr = &node;
+            while let Some(p) = prev.get(curr) {
+                path.push(p.clone());
+                curr = p;
+            }
+            path.reverse();
+            return Some((curr_cost, path));
+        }
+        if curr_cost > cost[&node] {
+            continue;
+        }
+        for (next, step_cost) in neighbours(&node) {
+            let next_cost = curr_cost + step_cost;
+            if cost.get(&next).is_none_or(|&c| next_cost < c) {
+                cost.insert(next.clone(), next_cost);
+                prev.insert(next.clone(), node.clone());
+                heap.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shortest_path() {
+        // 0 -> 1 -> 3 is cheaper than the direct 0 -> 3 edge
+        let edges = HashMap::from([
+            (0, vec![(1, 1), (2, 4), (3, 10)]),
+            (1, vec![(3, 2)]),
+            (2, vec![(3, 1)]),
+        ]);
+        let result = dijkstra(
+            0,
+            |n| edges.get(n).cloned().unwrap_or_default(),
+            |n| *n == 3,
+        );
+        assert_eq!(Some((3, vec![0, 1, 3])), result);
+        assert_eq!(None, dijkstra(3, |_| vec![], |n| *n == 0));
+    }
+}
@@ -0,0 +1,110 @@
+/// A dense rectangular grid indexed by (x, y), with (0, 0) top-left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from equally long rows, as returned by `parse::digit_grid`.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == width), "ragged grid rows");
+        Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, val: T) {
+        self.cells[y * self.width + x] = val;
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Orthogonal neighbours that lie inside the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        self.offsets(x, y, &[(0, -1), (0, 1), (-1, 0), (1, 0)])
+    }
+
+    /// Orthogonal and diagonal neighbours that lie inside the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        self.offsets(
+            x,
+            y,
+            &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        )
+    }
+
+    fn offsets(&self, x: usize, y: usize, deltas: &[(isize, isize)]) -> Vec<(usize, usize)> {
+        deltas
+            .iter()
+            .filter_map(|&(dx, dy)| {
+                let nx = x.checked_add_signed(dx)?;
+                let ny = y.checked_add_signed(dy)?;
+                (nx < self.width && ny < self.height).then_some((nx, ny))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&6), grid.get(2, 1));
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(vec![(0, 1), (1, 0)], grid.neighbours4(0, 0));
+        assert_eq!(5, grid.neighbours8(1, 0).len());
+        assert_eq!(6, grid.positions().count());
+    }
+}
@@ -0,0 +1,238 @@
+use crate::visual::{Frame, FrameSink, Rgb};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Pgm,
+    Png,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "ppm" => Some(Format::Ppm),
+            "pgm" => Some(Format::Pgm),
+            "png" => Some(Format::Png),
+            _ => None,
+        }
+    }
+}
+
+/// Row-major RGB pixels, one `scale` x `scale` block per frame cell. Cells
+/// take their background colour when set, otherwise their glyph colour.
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn from_frame(frame: &Frame, scale: usize) -> Self {
+        let width = frame.width() * scale;
+        let height = frame.height() * scale;
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let cell = frame.get(x / scale, y / scale);
+                pixels.push(cell.bg.unwrap_or(cell.fg));
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn encode(&self, format: Format) -> Vec<u8> {
+        match format {
+            Format::Ppm => self.ppm(),
+            Format::Pgm => self.pgm(),
+            Format::Png => self.png(),
+        }
+    }
+
+    fn ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for &Rgb(r, g, b) in &self.pixels {
+            out.extend([r, g, b]);
+        }
+        out
+    }
+
+    fn pgm(&self) -> Vec<u8> {
+        let mut out = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend(self.pixels.iter().map(|&p| luma(p)));
+        out
+    }
+
+    fn png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            // filter type 0, each scanline stored as is
+            raw.push(0);
+            for &Rgb(r, g, b) in row {
+                raw.extend([r, g, b]);
+            }
+        }
+
+        let mut ihdr = vec![];
+        ihdr.extend((self.width as u32).to_be_bytes());
+        ihdr.extend((self.height as u32).to_be_bytes());
+        // 8 bit depth, truecolour, deflate, no filter, no interlace
+        ihdr.extend([8, 2, 0, 0, 0]);
+
+        let mut out = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        png_chunk(&mut out, b"IHDR", &ihdr);
+        png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+}
+
+fn luma(Rgb(r, g, b): Rgb) -> u8 {
+    ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// zlib stream made of uncompressed deflate blocks, which keeps the encoder
+// dependency free at the cost of file size.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = 0xffff;
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+/// Saves the last frame it receives to `path`, in the format given by the
+/// file extension.
+pub struct ImageExporter {
+    path: PathBuf,
+    format: Format,
+    scale: usize,
+    last: Option<Frame>,
+}
+
+impl ImageExporter {
+    pub fn new(path: &Path, scale: usize) -> io::Result<Self> {
+        let format = Format::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "image path must end in .ppm, .pgm or .png",
+            )
+        })?;
+        Ok(ImageExporter {
+            path: path.to_path_buf(),
+            format,
+            scale: scale.max(1),
+            last: None,
+        })
+    }
+
+    pub fn save(&self) -> io::Result<bool> {
+        match &self.last {
+            Some(frame) => {
+                let image = Image::from_frame(frame, self.scale);
+                let mut file = BufWriter::new(File::create(&self.path)?);
+                file.write_all(&image.encode(self.format))?;
+                file.flush()?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+impl FrameSink for ImageExporter {
+    fn draw(&mut self, frame: &Frame) {
+        self.last = Some(frame.clone());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::visual::{Cell, BLACK, WHITE};
+
+    fn checker() -> Image {
+        let mut frame = Frame::new(2, 1);
+        frame.set(0, 0, Cell::new('#', WHITE));
+        frame.set(1, 0, Cell::new('.', WHITE).on(BLACK));
+        Image::from_frame(&frame, 2)
+    }
+
+    #[test]
+    fn netpbm() {
+        let image = checker();
+        assert_eq!((4, 2), (image.width, image.height));
+
+        let pgm = image.encode(Format::Pgm);
+        assert_eq!(b"P5\n4 2\n255\n".as_slice(), &pgm[..11]);
+        assert_eq!([255, 255, 0, 0, 255, 255, 0, 0], pgm[11..]);
+
+        let ppm = image.encode(Format::Ppm);
+        assert_eq!(11 + 4 * 2 * 3, ppm.len());
+    }
+
+    #[test]
+    fn png_checksums() {
+        assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+        assert_eq!(0x091e_01de, adler32(b"123456789"));
+
+        let png = checker().encode(Format::Png);
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!(b"IEND", &png[png.len() - 8..png.len() - 4]);
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(Some(Format::Png), Format::from_path(Path::new("a/b.PNG")));
+        assert_eq!(None, Format::from_path(Path::new("basins.jpg")));
+    }
+}
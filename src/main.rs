@@ -1,34 +1,85 @@
 use clap::{App, Arg};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::Duration;
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day15;
-mod day16;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+// these helpers go partly unused when only a subset of days is compiled in
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+mod bits;
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+mod graph;
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+mod grid;
+mod image;
+mod logger;
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+mod params;
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+mod parse;
+mod registry;
+#[cfg(test)]
+mod testing;
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+mod trie;
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+mod visual;
+// the day modules keep their tests above the entry points
+#[allow(clippy::items_after_test_module)]
+mod y2021;
+
+// `-` reads from stdin, e.g. to feed a solver an unbounded stream
+fn open_input(file_name: &str) -> std::io::Result<registry::Lines> {
+    if file_name == "-" {
+        return Ok(Box::new(std::io::stdin().lines().map(|l| l.unwrap())));
+    }
+    let file = File::open(file_name)?;
+    Ok(Box::new(BufReader::new(file).lines().map(|l| l.unwrap())))
+}
+
+// exits with the positioned message rather than the error's Debug form
+fn read_params(matches: &clap::ArgMatches) -> params::Params {
+    let args = matches.values_of("param").into_iter().flatten();
+    params::Params::from_args(args).unwrap_or_else(|e| {
+        clap::Error::with_description(
+            &format!("Invalid --param, {}", e),
+            clap::ErrorKind::InvalidValue,
+        )
+        .exit()
+    })
+}
+
+// like `read_params`, names the input file alongside the positioned message,
+// or the parameter when the solver rejected one
+fn solver_error(file_name: &str, e: parse::ParseError) -> ! {
+    let message = match &e.kind {
+        parse::ErrorKind::Param { arg, cause } => {
+            format!("Invalid --param {}, column {}: {}", arg, e.column, cause)
+        }
+        _ => format!("Invalid input {}, {}", file_name, e),
+    };
+    clap::Error::with_description(&message, clap::ErrorKind::InvalidValue).exit()
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let matches = App::new("aoc21")
         .version("1.0")
         .author("Smit Soni")
-        .about("Advent of Code 2021")
+        .about("Advent of Code")
+        .arg(
+            Arg::with_name("year")
+                .short("y")
+                .long("year")
+                .takes_value(true)
+                .default_value("2021")
+                .help("Year of the event"),
+        )
         .arg(
             Arg::with_name("day")
                 .short("d")
                 .long("day")
                 .takes_value(true)
-                .required(true)
+                .required_unless_one(&["list", "all"])
                 .help("Day of the calendar between 1-25"),
         )
         .arg(
@@ -36,16 +87,87 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .short("p")
                 .long("part")
                 .takes_value(true)
-                .required(true)
+                .required_unless_one(&["list", "all"])
                 .help("Which part of the puzzle, 1 or 2"),
         )
+        .arg(
+            Arg::with_name("param")
+                .short("P")
+                .long("param")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("key=value")
+                .help("Day specific setting, e.g. -P steps=40"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .multiple(true)
+                .help("Log solver progress to stderr, repeat for more detail"),
+        )
+        .arg(
+            Arg::with_name("list")
+                .long("list")
+                .help("List the days compiled into this build"),
+        )
+        .arg(
+            Arg::with_name("all")
+                .long("all")
+                .conflicts_with_all(&["day", "INPUT"])
+                .help("Solve every day of the year, both parts unless --part is given"),
+        )
+        .arg(
+            Arg::with_name("visualize")
+                .long("visualize")
+                .help("Draw the puzzle state in the terminal, for days that support it"),
+        )
+        .arg(
+            Arg::with_name("delay")
+                .long("delay")
+                .takes_value(true)
+                .default_value("100")
+                .help("Milliseconds between animation frames"),
+        )
+        .arg(
+            Arg::with_name("export-image")
+                .long("export-image")
+                .takes_value(true)
+                .value_name("path")
+                .help("Save the final puzzle state as a .ppm, .pgm or .png image"),
+        )
+        .arg(
+            Arg::with_name("image-scale")
+                .long("image-scale")
+                .takes_value(true)
+                .default_value("4")
+                .help("Pixels per grid cell in exported images"),
+        )
         .arg(
             Arg::with_name("INPUT")
                 .index(1)
-                .help("File with puzzle input"),
+                .help("File with puzzle input, - to read from stdin"),
         )
         .get_matches();
 
+    logger::init(matches.occurrences_of("verbose"));
+
+    if matches.is_present("list") {
+        for solver in registry::solvers() {
+            let extras = if solver.visualize.is_some() {
+                " (visualize)"
+            } else {
+                ""
+            };
+            println!("{} day {}{}", solver.year, solver.day, extras);
+        }
+        return Ok(());
+    }
+
+    let year = matches
+        .value_of("year")
+        .map_or(2021, |y| y.parse::<u16>().unwrap());
     let day = matches
         .value_of("day")
         .map_or(1, |d| d.parse::<u8>().unwrap());
@@ -64,31 +186,96 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    let def_file_name = format!("data/day{}.txt", day);
+    if matches.is_present("all") {
+        let params = read_params(&matches);
+        let parts = if matches.is_present("part") {
+            vec![part]
+        } else {
+            vec![1, 2]
+        };
+        for solver in registry::solvers().filter(|s| s.year == year) {
+            let file_name = format!("data/{}/day{}.txt", year, solver.day);
+            println!("day {}", solver.day);
+            for &part in &parts {
+                match open_input(&file_name) {
+                    Ok(lines) => {
+                        if let Err(e) = (solver.solve)(lines, part, &params) {
+                            println!("failed, {}: {}", file_name, e);
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        println!("skipped, {}: {}", file_name, e);
+                        break;
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let solver = match registry::find(year, day) {
+        Some(solver) => solver,
+        None => clap::Error::with_description(
+            &format!(
+                "Day {} of {} is not available in this build, available days: {:?}",
+                day,
+                year,
+                registry::days(year)
+            ),
+            clap::ErrorKind::InvalidValue,
+        )
+        .exit(),
+    };
+
+    let params = read_params(&matches);
+
+    let def_file_name = format!("data/{}/day{}.txt", year, day);
     let file_name = matches.value_of("INPUT").unwrap_or(&def_file_name);
+    let lines = open_input(file_name)?;
 
-    let file = File::open(file_name)?;
-    let lines = BufReader::new(file).lines().map(|l| l.unwrap());
-
-    match day {
-        1 => day1::solve(lines, part),
-        2 => day2::solve(lines, part),
-        3 => day3::solve(lines, part),
-        4 => day4::solve(lines, part),
-        5 => day5::solve(lines, part),
-        6 => day6::solve(lines, part),
-        7 => day7::solve(lines, part),
-        8 => day8::solve(lines, part),
-        9 => day9::solve(lines, part),
-        10 => day10::solve(lines, part),
-        11 => day11::solve(lines, part),
-        12 => day12::solve(lines, part),
-        13 => day13::solve(lines, part),
-        15 => day15::solve(lines, part),
-        16 => day16::solve(lines, part),
-        _ => {
-            println!("Unsolved");
+    let export = matches.value_of("export-image");
+    if matches.is_present("visualize") || export.is_some() {
+        let term = if matches.is_present("visualize") {
+            let delay = matches
+                .value_of("delay")
+                .map_or(100, |d| d.parse::<u64>().unwrap());
+            Some(visual::Terminal::new(Duration::from_millis(delay)))
+        } else {
+            None
+        };
+        let exporter = match export {
+            Some(path) => {
+                let scale = matches
+                    .value_of("image-scale")
+                    .map_or(4, |s| s.parse::<usize>().unwrap());
+                Some(image::ImageExporter::new(Path::new(path), scale)?)
+            }
+            None => None,
+        };
+
+        let mut sinks = (term, exporter);
+        match solver.visualize {
+            Some(visualize) => {
+                if let Err(e) = visualize(lines, part, &params, &mut sinks) {
+                    solver_error(file_name, e);
+                }
+            }
+            None => {
+                println!("No visualization for day {}", day);
+            }
+        }
+
+        if let (_, Some(exporter)) = &sinks {
+            if exporter.save()? {
+                println!("saved {}", export.unwrap());
+            }
         }
+        return Ok(());
+    }
+
+    if let Err(e) = (solver.solve)(lines, part, &params) {
+        solver_error(file_name, e);
     }
 
     Ok(())
@@ -0,0 +1,149 @@
+use crate::parse::{self, ErrorKind, ParseError};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::num::{ParseFloatError, ParseIntError};
+use std::str::{FromStr, ParseBoolError};
+
+/// Day specific settings given on the command line as `-P key=value`.
+#[derive(Default, Debug)]
+pub struct Params {
+    // the whole argument and the 0-based column its trimmed value starts at
+    values: HashMap<String, (String, usize)>,
+}
+
+impl Params {
+    pub fn from_args<'a, I>(args: I) -> parse::Result<Self>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let mut values = HashMap::new();
+        for arg in args {
+            let (key, value) = parse::key_value(arg, '=', |v| Ok(v.trim()))?;
+            if key.is_empty() {
+                return Err(ParseError::new(1, ErrorKind::Empty));
+            }
+            let start = arg.trim_end().len() - value.len();
+            values.insert(key.to_string(), (arg.to_string(), start));
+        }
+        Ok(Params { values })
+    }
+
+    /// Value of `key` read by `parse`. An error is positioned within the
+    /// `key=value` argument and names it, see `ErrorKind::Param`.
+    pub fn get_with<T, F>(&self, key: &str, parse: F) -> parse::Result<Option<T>>
+    where
+        F: Fn(&str) -> parse::Result<T>,
+    {
+        let (arg, start) = match self.values.get(key) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        let value = arg[*start..].trim_end();
+        parse(value).map(Some).map_err(|e| {
+            let kind = ErrorKind::Param {
+                arg: arg.clone(),
+                cause: Box::new(e.kind),
+            };
+            ParseError::new(start + e.column, kind)
+        })
+    }
+
+    /// Value of `key` parsed as `T`.
+    pub fn get<T>(&self, key: &str) -> parse::Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: ValueError,
+    {
+        self.get_with(key, |v| v.parse::<T>().map_err(|e| e.at_value(v)))
+    }
+
+    pub fn get_or<T>(&self, key: &str, default: T) -> parse::Result<T>
+    where
+        T: FromStr,
+        T::Err: ValueError,
+    {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+}
+
+/// A `FromStr` error turned into a positioned one about `value`. The
+/// puzzle's own types already report a `ParseError`.
+pub trait ValueError {
+    fn at_value(self, value: &str) -> ParseError;
+}
+
+impl ValueError for ParseError {
+    fn at_value(self, _: &str) -> ParseError {
+        self
+    }
+}
+
+impl ValueError for ParseIntError {
+    fn at_value(self, value: &str) -> ParseError {
+        ParseError::new(1, ErrorKind::InvalidNumber(value.to_string()))
+    }
+}
+
+impl ValueError for ParseFloatError {
+    fn at_value(self, value: &str) -> ParseError {
+        ParseError::new(1, ErrorKind::InvalidNumber(value.to_string()))
+    }
+}
+
+impl ValueError for ParseBoolError {
+    fn at_value(self, value: &str) -> ParseError {
+        let kind = ErrorKind::Unexpected {
+            found: value.to_string(),
+            expected: "true or false".to_string(),
+        };
+        ParseError::new(1, kind)
+    }
+}
+
+// strings take any value
+impl ValueError for Infallible {
+    fn at_value(self, _: &str) -> ParseError {
+        match self {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn key_values() {
+        let params = Params::from_args(["steps=40", "mode = aim"].into_iter()).unwrap();
+        assert_eq!(Some(40), params.get::<usize>("steps").unwrap());
+        assert_eq!(Some("aim".to_string()), params.get("mode").unwrap());
+        assert_eq!(3, params.get_or("window", 3).unwrap());
+
+        assert!(Params::from_args(["steps"].into_iter()).is_err());
+        assert!(Params::from_args(["=4"].into_iter()).is_err());
+    }
+
+    #[test]
+    fn bad_values() {
+        let args = ["steps=-4", "moves = yes", "target=3;4"];
+        let params = Params::from_args(args.into_iter()).unwrap();
+        let err = params.get::<usize>("steps").unwrap_err();
+        assert_eq!(7, err.column);
+        assert_eq!(
+            "column 7: invalid number '-4' in --param steps=-4",
+            err.to_string()
+        );
+
+        let err = params.get_or("moves", false).unwrap_err();
+        assert_eq!(9, err.column);
+        let err = params
+            .get_with("target", |v| parse::number_pair::<i64>(v, ","))
+            .unwrap_err();
+        assert_eq!(
+            ErrorKind::Param {
+                arg: "target=3;4".to_string(),
+                cause: Box::new(ErrorKind::MissingSeparator(",".to_string())),
+            },
+            err.kind
+        );
+    }
+}
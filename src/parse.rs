@@ -0,0 +1,378 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    Empty,
+    InvalidNumber(String),
+    InvalidDigit(char),
+    MissingSeparator(String),
+    MissingPrefix(String),
+    RaggedRow {
+        expected: usize,
+        found: usize,
+    },
+    Unexpected {
+        found: String,
+        expected: String,
+    },
+    /// A `-P key=value` a solver could not use, as `arg` was given.
+    Param {
+        arg: String,
+        cause: Box<ErrorKind>,
+    },
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Empty => write!(f, "unexpected empty input"),
+            ErrorKind::InvalidNumber(s) => write!(f, "invalid number '{}'", s),
+            ErrorKind::InvalidDigit(c) => write!(f, "invalid digit '{}'", c),
+            ErrorKind::MissingSeparator(s) => write!(f, "missing separator '{}'", s),
+            ErrorKind::MissingPrefix(s) => write!(f, "expected '{}'", s),
+            ErrorKind::RaggedRow { expected, found } => {
+                write!(f, "row has {} columns, expected {}", found, expected)
+            }
+            ErrorKind::Unexpected { found, expected } => {
+                write!(f, "unexpected '{}', expected {}", found, expected)
+            }
+            ErrorKind::Param { arg, cause } => write!(f, "{} in --param {}", cause, arg),
+        }
+    }
+}
+
+/// Parse failure with a 1-based position. A line of 0 means the parser
+/// was handed a single string and the caller did not attach a line number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ErrorKind,
+}
+
+impl ParseError {
+    pub fn new(column: usize, kind: ErrorKind) -> Self {
+        ParseError {
+            line: 0,
+            column,
+            kind,
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    fn shift(mut self, offset: usize) -> Self {
+        self.column += offset;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line > 0 {
+            write!(
+                f,
+                "line {}, column {}: {}",
+                self.line, self.column, self.kind
+            )
+        } else {
+            write!(f, "column {}: {}", self.column, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+fn leading_ws(s: &str) -> usize {
+    s.len() - s.trim_start().len()
+}
+
+/// Parses a single number, ignoring surrounding whitespace.
+pub fn number<T: FromStr>(s: &str) -> Result<T> {
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        return Err(ParseError::new(1, ErrorKind::Empty));
+    }
+    trimmed.parse::<T>().map_err(|_| {
+        ParseError::new(
+            1 + leading_ws(s),
+            ErrorKind::InvalidNumber(trimmed.to_string()),
+        )
+    })
+}
+
+/// Parses numbers separated by `sep`, e.g. `3,4,3,1,2`.
+pub fn number_list<T: FromStr>(s: &str, sep: char) -> Result<Vec<T>> {
+    let mut offset = 0;
+    let mut result = vec![];
+    for field in s.split(sep) {
+        result.push(number(field).map_err(|e| e.shift(offset))?);
+        offset += field.len() + sep.len_utf8();
+    }
+    Ok(result)
+}
+
+/// Parses whitespace separated numbers, e.g. a bingo board row ` 8  2 23`.
+pub fn number_fields<T: FromStr>(s: &str) -> Result<Vec<T>> {
+    let mut result = vec![];
+    let mut rest = s;
+    let mut offset = 0;
+    loop {
+        let ws = leading_ws(rest);
+        rest = &rest[ws..];
+        offset += ws;
+        if rest.is_empty() {
+            break;
+        }
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        result.push(number(&rest[..end]).map_err(|e| e.shift(offset))?);
+        rest = &rest[end..];
+        offset += end;
+    }
+    Ok(result)
+}
+
+/// Splits `s` at the first `sep` and hands each side to its own parser,
+/// e.g. `separated_pair("a-b", "-", ident, ident)`.
+pub fn separated_pair<'a, A, B, FA, FB>(
+    s: &'a str,
+    sep: &str,
+    first: FA,
+    second: FB,
+) -> Result<(A, B)>
+where
+    FA: Fn(&'a str) -> Result<A>,
+    FB: Fn(&'a str) -> Result<B>,
+{
+    let idx = s
+        .find(sep)
+        .ok_or_else(|| ParseError::new(1, ErrorKind::MissingSeparator(sep.to_string())))?;
+    let a = first(&s[..idx])?;
+    let rhs_offset = idx + sep.len();
+    let b = second(&s[rhs_offset..]).map_err(|e| e.shift(rhs_offset))?;
+    Ok((a, b))
+}
+
+/// Parses `x,y` into a pair of numbers.
+pub fn number_pair<T: FromStr>(s: &str, sep: &str) -> Result<(T, T)> {
+    separated_pair(s, sep, number, number)
+}
+
+/// Parses `key<sep>value` returning the trimmed key, e.g. `fold along y=7`.
+pub fn key_value<'a, V, F>(s: &'a str, sep: char, value: F) -> Result<(&'a str, V)>
+where
+    F: Fn(&'a str) -> Result<V>,
+{
+    let mut buf = [0; 4];
+    separated_pair(s, sep.encode_utf8(&mut buf), |k| Ok(k.trim()), value)
+}
+
+/// Strips `prefix` (after leading whitespace), e.g. `target area: `.
+pub fn prefixed<'a>(s: &'a str, prefix: &str) -> Result<&'a str> {
+    let ws = leading_ws(s);
+    s[ws..]
+        .strip_prefix(prefix)
+        .ok_or_else(|| ParseError::new(1 + ws, ErrorKind::MissingPrefix(prefix.to_string())))
+}
+
+/// Identity parser for bare words such as cave names.
+pub fn ident(s: &str) -> Result<&str> {
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        Err(ParseError::new(1, ErrorKind::Empty))
+    } else {
+        Ok(trimmed)
+    }
+}
+
+/// Applies `parse` to every non-blank line, attaching line numbers to errors.
+pub fn lines_with<I, T, V, F>(lines: I, parse: F) -> Result<Vec<V>>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+    F: Fn(&str) -> Result<V>,
+{
+    let mut result = vec![];
+    for (i, line) in lines.enumerate() {
+        let line = line.as_ref();
+        if line.trim().is_empty() {
+            continue;
+        }
+        result.push(parse(line).map_err(|e| e.at_line(i + 1))?);
+    }
+    Ok(result)
+}
+
+/// Applies `parse` to the first line of a one line input, such as a list
+/// of numbers, attaching the line number to errors.
+pub fn first_line_with<I, T, V, F>(mut lines: I, parse: F) -> Result<V>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+    F: Fn(&str) -> Result<V>,
+{
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::new(1, ErrorKind::Empty).at_line(1))?;
+    parse(line.as_ref()).map_err(|e| e.at_line(1))
+}
+
+/// A block of consecutive non-blank lines.
+pub struct Section {
+    pub first_line: usize,
+    pub lines: Vec<String>,
+}
+
+impl Section {
+    /// Parses every line of the section, attaching line numbers to errors.
+    pub fn parse_lines<V, F>(&self, parse: F) -> Result<Vec<V>>
+    where
+        F: Fn(&str) -> Result<V>,
+    {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, l)| parse(l).map_err(|e| e.at_line(self.first_line + i)))
+            .collect()
+    }
+}
+
+/// Groups input into blank-line separated sections.
+pub fn sections<I, T>(lines: I) -> Vec<Section>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let mut result = vec![];
+    let mut curr: Option<Section> = None;
+
+    for (i, line) in lines.enumerate() {
+        let line = line.as_ref();
+        if line.trim().is_empty() {
+            result.extend(curr.take());
+        } else {
+            curr.get_or_insert_with(|| Section {
+                first_line: i + 1,
+                lines: vec![],
+            })
+            .lines
+            .push(line.to_string());
+        }
+    }
+    result.extend(curr);
+    result
+}
+
+/// Parses a rectangular grid of single decimal digits, skipping blank lines.
+pub fn digit_grid<I, T>(lines: I) -> Result<Vec<Vec<u32>>>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let mut grid: Vec<Vec<u32>> = vec![];
+    for (i, line) in lines.enumerate() {
+        let line = line.as_ref();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let offset = leading_ws(line);
+        let row = line
+            .trim()
+            .chars()
+            .enumerate()
+            .map(|(ci, c)| {
+                let err = ParseError::new(offset + ci + 1, ErrorKind::InvalidDigit(c));
+                c.to_digit(10).ok_or_else(|| err.at_line(i + 1))
+            })
+            .collect::<Result<Vec<u32>>>()?;
+        if let Some(first) = grid.first() {
+            if first.len() != row.len() {
+                let kind = ErrorKind::RaggedRow {
+                    expected: first.len(),
+                    found: row.len(),
+                };
+                return Err(ParseError::new(1, kind).at_line(i + 1));
+            }
+        }
+        grid.push(row);
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lists_and_pairs() {
+        assert_eq!(
+            Ok(vec![3, 4, 3, 1, 2]),
+            number_list::<u32>("3,4,3,1,2", ',')
+        );
+        assert_eq!(Ok(vec![8, 2, 23]), number_fields::<u32>(" 8  2 23"));
+        assert_eq!(Ok((0, 9)), number_pair::<i32>("0,9", ","));
+        assert_eq!(
+            Ok(("fold along y", 7)),
+            key_value("fold along y=7", '=', number::<usize>)
+        );
+        assert_eq!(
+            Ok(((0, 9), (5, 9))),
+            separated_pair(
+                "0,9 -> 5,9",
+                "->",
+                |p| number_pair(p, ","),
+                |p| number_pair(p, ",")
+            )
+        );
+    }
+
+    #[test]
+    fn error_positions() {
+        let err = number_list::<u32>("3,4,x,1", ',').unwrap_err();
+        assert_eq!(5, err.column);
+        assert_eq!(ErrorKind::InvalidNumber("x".to_string()), err.kind);
+
+        let err = number_fields::<u32>(" 8  y 23").unwrap_err();
+        assert_eq!(5, err.column);
+
+        let err = separated_pair(
+            "0,9 -> 5,z",
+            "->",
+            |p| number_pair::<i32>(p, ","),
+            |p| number_pair::<i32>(p, ","),
+        )
+        .unwrap_err();
+        assert_eq!(10, err.column);
+
+        let err = digit_grid(["123", "", "1a3"].iter()).unwrap_err();
+        assert_eq!((3, 2), (err.line, err.column));
+        assert_eq!("line 3, column 2: invalid digit 'a'", err.to_string());
+
+        let err = digit_grid(["123", "12"].iter()).unwrap_err();
+        assert_eq!(
+            ErrorKind::RaggedRow {
+                expected: 3,
+                found: 2
+            },
+            err.kind
+        );
+    }
+
+    #[test]
+    fn blank_line_sections() {
+        let input = "\n1,2\n\n3 4\n5 6\n\n\n7\n";
+        let secs = sections(input.lines());
+        assert_eq!(3, secs.len());
+        assert_eq!(4, secs[1].first_line);
+        assert_eq!(vec!["3 4", "5 6"], secs[1].lines);
+
+        let rows = secs[1].parse_lines(number_fields::<u32>).unwrap();
+        assert_eq!(vec![vec![3, 4], vec![5, 6]], rows);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::params::Params;
+use crate::parse;
+use crate::visual::FrameSink;
+
+pub type Lines = Box<dyn Iterator<Item = String>>;
+
+pub type Visualize = fn(Lines, u8, &Params, &mut dyn FrameSink) -> parse::Result<()>;
+
+pub struct Solver {
+    pub year: u16,
+    pub day: u8,
+    pub solve: fn(Lines, u8, &Params) -> parse::Result<()>,
+    pub visualize: Option<Visualize>,
+}
+
+const YEARS: &[&[Solver]] = &[crate::y2021::SOLVERS];
+
+pub fn solvers() -> impl Iterator<Item = &'static Solver> {
+    YEARS.iter().flat_map(|year| year.iter())
+}
+
+pub fn find(year: u16, day: u8) -> Option<&'static Solver> {
+    solvers().find(|s| s.year == year && s.day == day)
+}
+
+pub fn days(year: u16) -> Vec<u8> {
+    solvers()
+        .filter(|s| s.year == year)
+        .map(|s| s.day)
+        .collect()
+}
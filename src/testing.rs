@@ -0,0 +1,23 @@
+// helpers shared by the tests of several modules
+
+/// A seeded linear congruential generator, so randomised tests see the same
+/// numbers on every run.
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg { state: seed }
+    }
+
+    /// The next number in `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        // the high bits are the random ones
+        (self.state >> 33) % n
+    }
+}
@@ -0,0 +1,359 @@
+use crate::bits::Bits;
+
+const ROOT: usize = 0;
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    // values stored below this node, duplicates included
+    count: usize,
+    children: [Option<usize>; 2],
+}
+
+/// A multiset of equally wide bit strings, branching on one bit per level
+/// from the most significant down. Nodes live in an arena and are recycled
+/// once everything below them is removed.
+#[derive(Clone, Debug)]
+pub struct BinaryTrie {
+    width: usize,
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+}
+
+// a general purpose structure, the puzzles only need some of its queries
+// and the others are allowed to go unused
+impl BinaryTrie {
+    pub fn new(width: usize) -> Self {
+        BinaryTrie {
+            width,
+            nodes: vec![Node::default()],
+            free: vec![],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes[ROOT].count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn count_at(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |n| self.nodes[n].count)
+    }
+
+    fn child(&self, node: usize, bit: bool) -> Option<usize> {
+        self.nodes[node].children[bit as usize]
+    }
+
+    fn alloc(&mut self) -> usize {
+        match self.free.pop() {
+            Some(node) => {
+                self.nodes[node] = Node::default();
+                node
+            }
+            None => {
+                self.nodes.push(Node::default());
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    pub fn insert(&mut self, value: &Bits) {
+        assert_eq!(self.width, value.width(), "value of the wrong width");
+        let mut node = ROOT;
+        self.nodes[node].count += 1;
+        for bit in value.iter() {
+            node = match self.child(node, bit) {
+                Some(next) => next,
+                None => {
+                    let next = self.alloc();
+                    self.nodes[node].children[bit as usize] = Some(next);
+                    next
+                }
+            };
+            self.nodes[node].count += 1;
+        }
+    }
+
+    /// Removes one copy of `value`, false if there was none.
+    #[allow(dead_code)]
+    pub fn remove(&mut self, value: &Bits) -> bool {
+        if self.count(value) == 0 {
+            return false;
+        }
+        let mut node = ROOT;
+        self.nodes[node].count -= 1;
+        for bit in value.iter() {
+            let next = self.child(node, bit).unwrap();
+            self.nodes[next].count -= 1;
+            if self.nodes[next].count == 0 {
+                // nothing left below, hand the whole branch back
+                self.nodes[node].children[bit as usize] = None;
+                self.release(next);
+                return true;
+            }
+            node = next;
+        }
+        true
+    }
+
+    fn release(&mut self, node: usize) {
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            stack.extend(self.nodes[node].children.iter().flatten());
+            self.free.push(node);
+        }
+    }
+
+    // the node reached by following `bits` from the root
+    fn find<B: Iterator<Item = bool>>(&self, bits: B) -> Option<usize> {
+        let mut node = ROOT;
+        for bit in bits {
+            node = self.child(node, bit)?;
+        }
+        Some(node)
+    }
+
+    /// How many copies of `value` the trie holds.
+    #[allow(dead_code)]
+    pub fn count(&self, value: &Bits) -> usize {
+        if value.width() != self.width {
+            return 0;
+        }
+        self.count_at(self.find(value.iter()))
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, value: &Bits) -> bool {
+        self.count(value) > 0
+    }
+
+    /// How many values start with `prefix`, which may be anything up to
+    /// the full width.
+    #[allow(dead_code)]
+    pub fn prefix_count(&self, prefix: &Bits) -> usize {
+        if prefix.width() > self.width {
+            return 0;
+        }
+        self.count_at(self.find(prefix.iter()))
+    }
+
+    // walks down to a value, asking `pick` for the bit to take wherever
+    // both exist. It gets the level and how many values lie on the zero and
+    // on the one side.
+    fn walk<F: FnMut(usize, usize, usize) -> bool>(&self, mut pick: F) -> Option<Bits> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut node = ROOT;
+        let mut bits = Vec::with_capacity(self.width);
+        for level in 0..self.width {
+            let bit = match self.nodes[node].children {
+                [Some(zero), Some(one)] => {
+                    pick(level, self.nodes[zero].count, self.nodes[one].count)
+                }
+                [Some(_), None] => false,
+                _ => true,
+            };
+            bits.push(bit);
+            node = self.child(node, bit).unwrap();
+        }
+        Some(bits.into_iter().collect())
+    }
+
+    /// Follows the ones where `follow_one(zeros, ones)` says so, given how
+    /// many values lie on either side, and the only side there is
+    /// otherwise.
+    pub fn descend<F: Fn(usize, usize) -> bool>(&self, follow_one: F) -> Option<Bits> {
+        self.walk(|_, zeros, ones| follow_one(zeros, ones))
+    }
+
+    #[allow(dead_code)]
+    pub fn min(&self) -> Option<Bits> {
+        self.walk(|_, _, _| false)
+    }
+
+    #[allow(dead_code)]
+    pub fn max(&self) -> Option<Bits> {
+        self.walk(|_, _, _| true)
+    }
+
+    /// The value that differs from `key` in the most significant bits,
+    /// maximising their XOR.
+    #[allow(dead_code)]
+    pub fn max_xor(&self, key: &Bits) -> Option<Bits> {
+        assert_eq!(self.width, key.width(), "key of the wrong width");
+        self.walk(|level, _, _| !key.get(level))
+    }
+
+    /// The value closest to `key` in XOR distance, `key` itself if present.
+    #[allow(dead_code)]
+    pub fn min_xor(&self, key: &Bits) -> Option<Bits> {
+        assert_eq!(self.width, key.width(), "key of the wrong width");
+        self.walk(|level, _, _| key.get(level))
+    }
+
+    /// The value of rank `k` in ascending order, from 0 and counting
+    /// duplicates.
+    #[allow(dead_code)]
+    pub fn nth(&self, mut k: usize) -> Option<Bits> {
+        if k >= self.len() {
+            return None;
+        }
+        self.walk(|_, zeros, _| {
+            if k < zeros {
+                false
+            } else {
+                k -= zeros;
+                true
+            }
+        })
+    }
+
+    /// Every distinct value in ascending order, with its number of copies.
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = (Bits, usize)> + '_ {
+        let mut stack = vec![];
+        if !self.is_empty() {
+            stack.push((ROOT, vec![]));
+        }
+        std::iter::from_fn(move || {
+            while let Some((node, bits)) = stack.pop() {
+                if bits.len() == self.width {
+                    let count = self.nodes[node].count;
+                    return Some((bits.into_iter().collect(), count));
+                }
+                // the one side goes first so the zero side pops first
+                for bit in [true, false] {
+                    if let Some(child) = self.child(node, bit) {
+                        let mut path = bits.clone();
+                        path.push(bit);
+                        stack.push((child, path));
+                    }
+                }
+            }
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Lcg;
+
+    fn bits(s: &str) -> Bits {
+        Bits::parse(s).unwrap()
+    }
+
+    #[test]
+    fn queries() {
+        let mut trie = BinaryTrie::new(4);
+        for value in ["0110", "1010", "0110", "0001", "1111"] {
+            trie.insert(&bits(value));
+        }
+        assert_eq!(5, trie.len());
+        assert_eq!(2, trie.count(&bits("0110")));
+        assert_eq!(0, trie.count(&bits("0111")));
+        assert_eq!(3, trie.prefix_count(&bits("0")));
+        assert_eq!(2, trie.prefix_count(&bits("011")));
+        assert_eq!(5, trie.prefix_count(&Bits::zeros(0)));
+
+        let sorted: Vec<(String, usize)> = trie.iter().map(|(b, n)| (b.to_string(), n)).collect();
+        assert_eq!(
+            vec![
+                ("0001".to_string(), 1),
+                ("0110".to_string(), 2),
+                ("1010".to_string(), 1),
+                ("1111".to_string(), 1)
+            ],
+            sorted
+        );
+        assert_eq!(Some(bits("0110")), trie.nth(2));
+        assert_eq!(Some(bits("1111")), trie.nth(4));
+        assert_eq!(None, trie.nth(5));
+        assert_eq!(
+            (Some(bits("0001")), Some(bits("1111"))),
+            (trie.min(), trie.max())
+        );
+
+        // 0101 is furthest from 1010 and closest to 0100 and 0111
+        assert_eq!(Some(bits("1010")), trie.max_xor(&bits("0101")));
+        assert_eq!(Some(bits("0110")), trie.min_xor(&bits("0111")));
+
+        assert!(trie.remove(&bits("0110")));
+        assert!(trie.remove(&bits("0110")));
+        assert!(!trie.remove(&bits("0110")));
+        assert_eq!(1, trie.prefix_count(&bits("0")));
+        assert_eq!(Some(bits("0001")), trie.min_xor(&bits("0111")));
+    }
+
+    #[test]
+    fn recycles_nodes() {
+        let mut trie = BinaryTrie::new(8);
+        let value = bits("10110011");
+        trie.insert(&value);
+        let nodes = trie.nodes.len();
+        trie.remove(&value);
+        assert!(trie.is_empty() && trie.min().is_none());
+        trie.insert(&bits("01001100"));
+        assert_eq!(nodes, trie.nodes.len());
+    }
+
+    #[test]
+    fn matches_sorted_list() {
+        let width = 7;
+        let mut rng = Lcg::new(42);
+        let to_bits = |n: u64| -> Bits { (0..width).rev().map(|i| n >> i & 1 == 1).collect() };
+
+        let mut trie = BinaryTrie::new(width);
+        let mut list: Vec<u64> = vec![];
+        for _ in 0..2000 {
+            let n = rng.below(128);
+            if rng.below(3) == 0 {
+                let pos = list.iter().position(|&m| m == n);
+                assert_eq!(pos.is_some(), trie.remove(&to_bits(n)));
+                if let Some(pos) = pos {
+                    list.remove(pos);
+                }
+            } else {
+                trie.insert(&to_bits(n));
+                list.push(n);
+            }
+            list.sort();
+
+            let key = rng.below(128);
+            let max_xor = list.iter().map(|&m| m ^ key).max();
+            let min_xor = list.iter().map(|&m| m ^ key).min();
+            assert_eq!(
+                max_xor.map(|x| to_bits(x ^ key)),
+                trie.max_xor(&to_bits(key))
+            );
+            assert_eq!(
+                min_xor.map(|x| to_bits(x ^ key)),
+                trie.min_xor(&to_bits(key))
+            );
+            let k = rng.below(list.len() as u64 + 1) as usize;
+            assert_eq!(list.get(k).map(|&m| to_bits(m)), trie.nth(k));
+            let prefix: Bits = (0..3).rev().map(|i| key >> i & 1 == 1).collect();
+            let prefixed = list
+                .iter()
+                .filter(|&&m| m >> (width - 3) == key & 7)
+                .count();
+            assert_eq!(prefixed, trie.prefix_count(&prefix));
+        }
+        let mut expanded = vec![];
+        for (value, count) in trie.iter() {
+            expanded.extend(std::iter::repeat_n(value, count));
+        }
+        assert_eq!(
+            list.iter().map(|&m| to_bits(m)).collect::<Vec<_>>(),
+            expanded
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+pub const BLACK: Rgb = Rgb(0, 0, 0);
+pub const GREY: Rgb = Rgb(90, 90, 90);
+pub const WHITE: Rgb = Rgb(255, 255, 255);
+pub const YELLOW: Rgb = Rgb(255, 215, 0);
+
+/// Maps `value` in `0..=max` onto a blue to red ramp.
+pub fn heat(value: u32, max: u32) -> Rgb {
+    if max == 0 {
+        return Rgb(0, 0, 255);
+    }
+    let t = value.min(max) as f64 / max as f64;
+    let (r, g, b) = if t < 0.5 {
+        (0.0, 2.0 * t, 1.0 - 2.0 * t)
+    } else {
+        (2.0 * t - 1.0, 2.0 - 2.0 * t, 0.0)
+    };
+    Rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}
+
+/// Distinct colours for labelled regions, cycling after a dozen.
+pub fn label(index: usize) -> Rgb {
+    const PALETTE: [Rgb; 12] = [
+        Rgb(230, 25, 75),
+        Rgb(60, 180, 75),
+        Rgb(255, 225, 25),
+        Rgb(67, 99, 216),
+        Rgb(245, 130, 49),
+        Rgb(145, 30, 180),
+        Rgb(66, 212, 244),
+        Rgb(240, 50, 230),
+        Rgb(191, 239, 69),
+        Rgb(250, 190, 212),
+        Rgb(70, 153, 144),
+        Rgb(220, 190, 255),
+    ];
+    PALETTE[index % PALETTE.len()]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub fg: Rgb,
+    pub bg: Option<Rgb>,
+}
+
+impl Cell {
+    pub fn new(glyph: char, fg: Rgb) -> Self {
+        Cell {
+            glyph,
+            fg,
+            bg: None,
+        }
+    }
+
+    pub fn on(mut self, bg: Rgb) -> Self {
+        self.bg = Some(bg);
+        self
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell::new(' ', WHITE)
+    }
+}
+
+/// A snapshot of a grid shaped puzzle state, indexed by (x, y).
+#[derive(Clone, Debug)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+            caption: String::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Cell {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        self.cells[y * self.width + x] = cell;
+    }
+
+    pub fn with_caption(mut self, caption: String) -> Self {
+        self.caption = caption;
+        self
+    }
+
+    /// Renders the frame with 24-bit ANSI colour escapes.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = self.get(x, y);
+                let Rgb(r, g, b) = cell.fg;
+                out.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+                if let Some(Rgb(r, g, b)) = cell.bg {
+                    out.push_str(&format!("\x1b[48;2;{};{};{}m", r, g, b));
+                }
+                out.push(cell.glyph);
+                out.push_str("\x1b[0m");
+            }
+            out.push('\n');
+        }
+        if !self.caption.is_empty() {
+            out.push_str(&self.caption);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Renders only the glyphs, for plain text output.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.height {
+            let row: String = (0..self.width).map(|x| self.get(x, y).glyph).collect();
+            out.push_str(row.trim_end());
+            out.push('\n');
+        }
+        if !self.caption.is_empty() {
+            out.push_str(&self.caption);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Receives the frames a day produces while it is being solved.
+pub trait FrameSink {
+    fn draw(&mut self, frame: &Frame);
+}
+
+impl<S: FrameSink> FrameSink for Option<S> {
+    fn draw(&mut self, frame: &Frame) {
+        if let Some(sink) = self {
+            sink.draw(frame);
+        }
+    }
+}
+
+impl<A: FrameSink, B: FrameSink> FrameSink for (A, B) {
+    fn draw(&mut self, frame: &Frame) {
+        self.0.draw(frame);
+        self.1.draw(frame);
+    }
+}
+
+/// Draws frames in place on an ANSI terminal, pausing between them.
+pub struct Terminal {
+    delay: Duration,
+}
+
+impl Terminal {
+    pub fn new(delay: Duration) -> Self {
+        Terminal { delay }
+    }
+}
+
+impl FrameSink for Terminal {
+    fn draw(&mut self, frame: &Frame) {
+        let mut stdout = io::stdout();
+        // clear screen and move cursor home before every frame
+        let _ = write!(stdout, "\x1b[2J\x1b[H{}", frame.to_ansi());
+        let _ = stdout.flush();
+        thread::sleep(self.delay);
+    }
+}
+
+/// Keeps every frame it is handed.
+#[cfg(test)]
+#[derive(Default)]
+pub struct Recorder {
+    pub frames: Vec<Frame>,
+}
+
+#[cfg(test)]
+impl FrameSink for Recorder {
+    fn draw(&mut self, frame: &Frame) {
+        self.frames.push(frame.clone());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ansi_output() {
+        let mut frame = Frame::new(2, 1).with_caption("done".to_string());
+        frame.set(0, 0, Cell::new('#', WHITE));
+        frame.set(1, 0, Cell::new('.', BLACK).on(YELLOW));
+        assert_eq!(
+            "\x1b[38;2;255;255;255m#\x1b[0m\
+             \x1b[38;2;0;0;0m\x1b[48;2;255;215;0m.\x1b[0m\ndone\n",
+            frame.to_ansi()
+        );
+    }
+
+    #[test]
+    fn text_output() {
+        let mut frame = Frame::new(3, 2).with_caption("done".to_string());
+        frame.set(0, 0, Cell::new('#', WHITE));
+        frame.set(1, 0, Cell::new('.', BLACK).on(YELLOW));
+        // trailing blanks are dropped, colours ignored
+        assert_eq!("#.\n\ndone\n", frame.to_text());
+    }
+
+    #[test]
+    fn heat_ramp() {
+        assert_eq!(Rgb(0, 0, 255), heat(0, 10));
+        assert_eq!(Rgb(255, 0, 0), heat(10, 10));
+        assert_eq!(heat(10, 10), heat(20, 10));
+    }
+}
@@ -0,0 +1,592 @@
+use crate::params::Params;
+use crate::parse::{self, ErrorKind, ParseError};
+use std::fmt;
+use std::str::FromStr;
+
+// the most recent `size` readings in a ring buffer, with their running sum.
+// The sum is kept in a u128, which fits up to 2^64 readings of any size.
+struct Window {
+    size: usize,
+    readings: Vec<u64>,
+    next: usize,
+    sum: u128,
+}
+
+impl Window {
+    fn new(size: usize) -> Self {
+        assert!(size > 0, "window size must be at least 1");
+        Window {
+            size,
+            readings: Vec::with_capacity(size),
+            next: 0,
+            sum: 0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.readings.len() == self.size
+    }
+
+    // adds a reading, evicting the oldest one once the window is full
+    fn push(&mut self, reading: u64) {
+        if self.is_full() {
+            self.sum -= self.readings[self.next] as u128;
+            self.readings[self.next] = reading;
+            self.next = (self.next + 1) % self.size;
+        } else {
+            self.readings.push(reading);
+        }
+        self.sum += reading as u128;
+    }
+
+    fn aggregate(&self, aggregate: Aggregate) -> Value {
+        let readings = &self.readings;
+        match aggregate {
+            Aggregate::Sum => Value::Int(self.sum),
+            Aggregate::Mean => Value::Real(self.sum as f64 / readings.len() as f64),
+            Aggregate::Min => Value::Int(*readings.iter().min().unwrap() as u128),
+            Aggregate::Max => Value::Int(*readings.iter().max().unwrap() as u128),
+            Aggregate::Median => {
+                let mut sorted = readings.clone();
+                sorted.sort_unstable();
+                let mid = sorted.len() / 2;
+                if sorted.len() % 2 == 1 {
+                    return Value::Int(sorted[mid] as u128);
+                }
+                // halfway between the middle two without adding them up
+                let (low, high) = (sorted[mid - 1], sorted[mid]);
+                let gap = high - low;
+                if gap % 2 == 0 {
+                    Value::Int((low + gap / 2) as u128)
+                } else {
+                    Value::Real(low as f64 + gap as f64 / 2.0)
+                }
+            }
+        }
+    }
+}
+
+// a window's aggregate, kept as an integer where it is one so sums too
+// large for an f64 still compare exactly
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Value {
+    Int(u128),
+    Real(f64),
+}
+
+impl Value {
+    fn as_f64(self) -> f64 {
+        match self {
+            Value::Int(n) => n as f64,
+            Value::Real(x) => x,
+        }
+    }
+
+    // the change from `prev`, its sign exact even when the values aren't
+    fn since(self, prev: Value) -> f64 {
+        match (self, prev) {
+            (Value::Int(curr), Value::Int(prev)) => (curr as i128 - prev as i128) as f64,
+            _ => self.as_f64() - prev.as_f64(),
+        }
+    }
+}
+
+/// What a window of readings is summarised as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregate {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Median,
+}
+
+impl FromStr for Aggregate {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> parse::Result<Self> {
+        match s {
+            "sum" => Ok(Aggregate::Sum),
+            "mean" => Ok(Aggregate::Mean),
+            "min" => Ok(Aggregate::Min),
+            "max" => Ok(Aggregate::Max),
+            "median" => Ok(Aggregate::Median),
+            _ => Err(ParseError::new(
+                1,
+                ErrorKind::Unexpected {
+                    found: s.to_string(),
+                    expected: "sum, mean, min, max or median".to_string(),
+                },
+            )),
+        }
+    }
+}
+
+/// Which change from one window to the next counts as a match.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparator {
+    Greater,
+    Less,
+    GreaterBy(f64),
+}
+
+impl Comparator {
+    fn holds(&self, prev: Value, curr: Value) -> bool {
+        match (prev, curr) {
+            (Value::Int(prev), Value::Int(curr)) => {
+                let change = curr as i128 - prev as i128;
+                match *self {
+                    Comparator::Greater => change > 0,
+                    Comparator::Less => change < 0,
+                    // a whole change exceeds the threshold when it
+                    // exceeds its whole part
+                    Comparator::GreaterBy(threshold) => change > threshold.floor() as i128,
+                }
+            }
+            _ => {
+                let (prev, curr) = (prev.as_f64(), curr.as_f64());
+                match *self {
+                    Comparator::Greater => curr > prev,
+                    Comparator::Less => curr < prev,
+                    Comparator::GreaterBy(threshold) => curr - prev > threshold,
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Comparator {
+    type Err = ParseError;
+
+    // `greater`, `less` or `greater-by:N`
+    fn from_str(s: &str) -> parse::Result<Self> {
+        match s {
+            "greater" => Ok(Comparator::Greater),
+            "less" => Ok(Comparator::Less),
+            _ => match parse::key_value(s, ':', parse::number) {
+                Ok(("greater-by", threshold)) => Ok(Comparator::GreaterBy(threshold)),
+                // a bad threshold is reported where it is
+                Err(e) if s.starts_with("greater-by:") => Err(e),
+                _ => Err(ParseError::new(
+                    1,
+                    ErrorKind::Unexpected {
+                        found: s.to_string(),
+                        expected: "greater, less or greater-by:N".to_string(),
+                    },
+                )),
+            },
+        }
+    }
+}
+
+/// How much `solve` reports besides the answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Report {
+    /// The statistics of the whole sweep.
+    Summary,
+    /// The statistics and every window as it is read.
+    Trend,
+}
+
+impl FromStr for Report {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> parse::Result<Self> {
+        match s {
+            "summary" => Ok(Report::Summary),
+            "trend" => Ok(Report::Trend),
+            _ => Err(ParseError::new(
+                1,
+                ErrorKind::Unexpected {
+                    found: s.to_string(),
+                    expected: "summary or trend".to_string(),
+                },
+            )),
+        }
+    }
+}
+
+/// Windows of `size` readings, each summarised by `aggregate` and compared
+/// with the previous one by `comparator`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowSpec {
+    pub size: usize,
+    pub aggregate: Aggregate,
+    pub comparator: Comparator,
+}
+
+impl WindowSpec {
+    /// Rising window sums, as counted by the puzzle.
+    pub fn sums(size: usize) -> Self {
+        WindowSpec {
+            size,
+            aggregate: Aggregate::Sum,
+            comparator: Comparator::Greater,
+        }
+    }
+}
+
+fn readings<I>(input: I) -> impl Iterator<Item = parse::Result<u64>>
+where
+    I: Iterator<Item = String>,
+{
+    input
+        .enumerate()
+        .filter(|(_, s)| !s.trim().is_empty())
+        .map(|(i, s)| parse::number::<u64>(&s).map_err(|e| e.at_line(i + 1)))
+}
+
+/// A window of the sweep, compared with the one before it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowTrend {
+    /// Index of the window's first reading.
+    pub index: usize,
+    pub value: f64,
+    /// None for the first window.
+    pub change: Option<f64>,
+    /// Whether the comparator holds against the previous window.
+    pub matches: bool,
+}
+
+impl fmt::Display for WindowTrend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.index, self.value)?;
+        if let Some(change) = self.change {
+            write!(f, " ({:+})", change)?;
+        }
+        Ok(())
+    }
+}
+
+// yields every full window as the readings stream in
+struct Trends<R> {
+    readings: R,
+    window: Window,
+    spec: WindowSpec,
+    index: usize,
+    prev: Option<Value>,
+}
+
+impl<R: Iterator<Item = parse::Result<u64>>> Iterator for Trends<R> {
+    type Item = parse::Result<WindowTrend>;
+
+    fn next(&mut self) -> Option<parse::Result<WindowTrend>> {
+        loop {
+            match self.readings.next()? {
+                Ok(reading) => self.window.push(reading),
+                Err(e) => return Some(Err(e)),
+            }
+            if self.window.is_full() {
+                break;
+            }
+        }
+        let value = self.window.aggregate(self.spec.aggregate);
+        let trend = WindowTrend {
+            index: self.index,
+            value: value.as_f64(),
+            change: self.prev.map(|prev| value.since(prev)),
+            matches: self
+                .prev
+                .is_some_and(|prev| self.spec.comparator.holds(prev, value)),
+        };
+        self.index += 1;
+        self.prev = Some(value);
+        Some(Ok(trend))
+    }
+}
+
+fn trends<I>(input: I, spec: &WindowSpec) -> Trends<impl Iterator<Item = parse::Result<u64>>>
+where
+    I: Iterator<Item = String>,
+{
+    Trends {
+        readings: readings(input),
+        window: Window::new(spec.size),
+        spec: *spec,
+        index: 0,
+        prev: None,
+    }
+}
+
+/// Statistics over consecutive windows of a sonar sweep.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SonarReport {
+    pub windows: usize,
+    /// Windows for which the comparator holds.
+    pub matches: usize,
+    pub increases: usize,
+    pub decreases: usize,
+    pub equal: usize,
+    /// Start index and length, in windows, of the first longest strictly
+    /// increasing run.
+    pub longest_run: (usize, usize),
+    /// Window index and change of the largest rise or fall.
+    pub largest_jump: Option<(usize, f64)>,
+    run: (usize, usize),
+}
+
+impl SonarReport {
+    fn add(&mut self, trend: &WindowTrend) {
+        self.windows += 1;
+        self.matches += trend.matches as usize;
+        match trend.change {
+            Some(change) if change > 0.0 => {
+                self.increases += 1;
+                self.run.1 += 1;
+            }
+            Some(change) => {
+                if change < 0.0 {
+                    self.decreases += 1;
+                } else {
+                    self.equal += 1;
+                }
+                self.run = (trend.index, 1);
+            }
+            None => self.run = (trend.index, 1),
+        }
+        if self.run.1 > self.longest_run.1 {
+            self.longest_run = self.run;
+        }
+        if let Some(change) = trend.change {
+            if self
+                .largest_jump
+                .is_none_or(|(_, jump)| change.abs() > jump.abs())
+            {
+                self.largest_jump = Some((trend.index, change));
+            }
+        }
+    }
+}
+
+impl fmt::Display for SonarReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "windows: {}, matches: {}, increases: {}, decreases: {}, equal: {}",
+            self.windows, self.matches, self.increases, self.decreases, self.equal
+        )?;
+        write!(
+            f,
+            "longest increasing run: {} windows from index {}",
+            self.longest_run.1, self.longest_run.0
+        )?;
+        if let Some((index, change)) = self.largest_jump {
+            write!(f, "\nlargest jump: {:+} at index {}", change, index)?;
+        }
+        Ok(())
+    }
+}
+
+// streams the input, keeping only the last `spec.size` readings around.
+// `each` sees every window on the way.
+pub fn sonar_report<I, F>(input: I, spec: &WindowSpec, mut each: F) -> parse::Result<SonarReport>
+where
+    I: Iterator<Item = String>,
+    F: FnMut(&WindowTrend),
+{
+    let mut report = SonarReport::default();
+    for trend in trends(input, spec) {
+        let trend = trend?;
+        each(&trend);
+        report.add(&trend);
+    }
+    Ok(report)
+}
+
+pub fn num_of_window_matches<I>(input: I, spec: &WindowSpec) -> parse::Result<usize>
+where
+    I: Iterator<Item = String>,
+{
+    Ok(sonar_report(input, spec, |_| {})?.matches)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DEPTHS: [u64; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    fn lines() -> impl Iterator<Item = String> {
+        DEPTHS.iter().map(|n| n.to_string())
+    }
+
+    #[test]
+    fn test_window_increments() {
+        assert_eq!(num_of_window_matches(lines(), &WindowSpec::sums(1)), Ok(7));
+        assert_eq!(num_of_window_matches(lines(), &WindowSpec::sums(3)), Ok(5));
+
+        let bad = ["199", "", "20x"].into_iter().map(String::from);
+        let err = num_of_window_matches(bad, &WindowSpec::sums(1)).unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column));
+    }
+
+    #[test]
+    fn window_sizes() {
+        for size in 1..=12 {
+            let sums: Vec<u64> = DEPTHS.windows(size).map(|w| w.iter().sum()).collect();
+            let expected = sums.windows(2).filter(|w| w[1] > w[0]).count();
+            assert_eq!(
+                Ok(expected),
+                num_of_window_matches(lines(), &WindowSpec::sums(size))
+            );
+        }
+    }
+
+    #[test]
+    fn report() {
+        let report = sonar_report(lines(), &WindowSpec::sums(1), |_| {}).unwrap();
+        assert_eq!(
+            (10, 7, 2, 0),
+            (
+                report.windows,
+                report.increases,
+                report.decreases,
+                report.equal
+            )
+        );
+        // 199..210 and 200..269 both rise four times in a row, the first wins
+        assert_eq!((0, 4), report.longest_run);
+        assert_eq!(Some((6, 33.0)), report.largest_jump);
+
+        // 607 618 618 617 647 716 769 792
+        let report = sonar_report(lines(), &WindowSpec::sums(3), |_| {}).unwrap();
+        assert_eq!(
+            (8, 5, 1, 1),
+            (
+                report.windows,
+                report.increases,
+                report.decreases,
+                report.equal
+            )
+        );
+        assert_eq!((3, 5), report.longest_run);
+        assert_eq!(Some((5, 69.0)), report.largest_jump);
+
+        let first: Vec<String> = trends(lines(), &WindowSpec::sums(3))
+            .take(2)
+            .map(|t| t.unwrap().to_string())
+            .collect();
+        assert_eq!(vec!["0: 607", "1: 618 (+11)"], first);
+        assert_eq!(
+            Ok(SonarReport::default()),
+            sonar_report(lines(), &WindowSpec::sums(11), |_| {})
+        );
+    }
+
+    fn matches(size: usize, aggregate: &str, comparator: Comparator) -> usize {
+        let spec = WindowSpec {
+            size,
+            aggregate: aggregate.parse().unwrap(),
+            comparator,
+        };
+        num_of_window_matches(lines(), &spec).unwrap()
+    }
+
+    #[test]
+    fn aggregates_and_comparators() {
+        // medians of 5: 200 207 208 210 240 260
+        assert_eq!(2, matches(5, "median", Comparator::GreaterBy(10.0)));
+        assert_eq!(5, matches(5, "median", Comparator::Greater));
+        // medians of 2 are their means, 199.5 200.5 ..
+        assert_eq!(
+            matches(2, "mean", Comparator::GreaterBy(0.5)),
+            matches(2, "median", Comparator::GreaterBy(0.5))
+        );
+        assert_eq!(
+            matches(2, "sum", Comparator::Greater),
+            matches(2, "mean", Comparator::Greater)
+        );
+        assert_eq!(1, matches(3, "sum", Comparator::Less));
+        // maxima of 3: 208 210 210 210 240 269 269 269
+        assert_eq!(3, matches(3, "max", Comparator::Greater));
+        // minima of 2: 199 200 208 200 200 207 240 260 260
+        assert_eq!(5, matches(2, "min", Comparator::Greater));
+        assert_eq!(
+            "column 1: unexpected 'mode', expected sum, mean, min, max or median",
+            "mode".parse::<Aggregate>().unwrap_err().to_string()
+        );
+
+        assert_eq!(Ok(Comparator::GreaterBy(2.5)), "greater-by:2.5".parse());
+        assert_eq!(Ok(Comparator::Less), "less".parse());
+        assert_eq!(
+            "column 1: unexpected 'greater-by', expected greater, less or greater-by:N",
+            "greater-by".parse::<Comparator>().unwrap_err().to_string()
+        );
+        assert_eq!(12, "greater-by:x".parse::<Comparator>().unwrap_err().column);
+        assert_eq!(Ok(Report::Trend), "trend".parse());
+        assert!("trends".parse::<Report>().is_err());
+    }
+
+    #[test]
+    fn exact_sums() {
+        // window sums beyond 2^53, which an f64 would round to one value
+        let big = 1u64 << 60;
+        let depths = [big, 1, big, 2, big, 3];
+        let matches = |comparator| {
+            let spec = WindowSpec {
+                comparator,
+                ..WindowSpec::sums(2)
+            };
+            num_of_window_matches(depths.iter().map(|n| n.to_string()), &spec).unwrap()
+        };
+        assert_eq!(2, matches(Comparator::Greater));
+        assert_eq!(2, matches(Comparator::GreaterBy(0.5)));
+        assert_eq!(0, matches(Comparator::GreaterBy(1.0)));
+        assert_eq!(0, matches(Comparator::Less));
+
+        // neither the sums nor the medians of two fit in a u64
+        let max = u64::MAX;
+        let depths = [max - 4, max, max - 2, max, max - 4];
+        let matches = |aggregate| {
+            let spec = WindowSpec {
+                aggregate,
+                ..WindowSpec::sums(2)
+            };
+            let lines = depths.iter().map(|n| n.to_string());
+            let report = sonar_report(lines, &spec, |_| {}).unwrap();
+            (report.increases, report.decreases)
+        };
+        assert_eq!((1, 1), matches(Aggregate::Sum));
+        assert_eq!((1, 1), matches(Aggregate::Median));
+    }
+}
+
+fn window_spec(part: u8, params: &Params) -> parse::Result<WindowSpec> {
+    let size = params.get("window")?.unwrap_or(match part {
+        1 => 1,
+        2 => 3,
+        _ => unreachable!(),
+    });
+    let sums = WindowSpec::sums(size);
+    Ok(WindowSpec {
+        aggregate: params.get_or("aggregate", sums.aggregate)?,
+        comparator: params.get_or("compare", sums.comparator)?,
+        ..sums
+    })
+}
+
+// windows are compared as `-P window=N -P aggregate=median
+// -P compare=greater-by:10` asks. `-P report=summary` adds the statistics
+// to the answer, `-P report=trend` also lists every window as it is read.
+pub fn solve<I>(lines: I, part: u8, params: &Params) -> parse::Result<()>
+where
+    I: Iterator<Item = String>,
+{
+    let spec = window_spec(part, params)?;
+    let mode = match params.get::<Report>("report")? {
+        Some(mode) => mode,
+        None => {
+            println!("part{}: {}", part, num_of_window_matches(lines, &spec)?);
+            return Ok(());
+        }
+    };
+
+    let report = sonar_report(lines, &spec, |trend| {
+        if mode == Report::Trend {
+            println!("{}", trend);
+        }
+    })?;
+    println!("part{}: {}", part, report.matches);
+    println!("{}", report);
+    Ok(())
+}
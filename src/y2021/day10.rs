@@ -1,3 +1,4 @@
+use crate::parse;
 use std::collections::HashMap;
 
 enum Line {
@@ -106,7 +107,7 @@ mod test {
     }
 }
 
-pub fn solve<I>(lines: I, part: u8)
+pub fn solve<I>(lines: I, part: u8) -> parse::Result<()>
 where
     I: Iterator<Item = String>,
 {
@@ -115,4 +116,5 @@ where
         2 => println!("part2: {}", part2(lines)),
         _ => {}
     }
+    Ok(())
 }
@@ -1,8 +1,11 @@
+use crate::grid::Grid;
+use crate::parse;
+use crate::visual::{self, Cell, Frame, FrameSink};
 use std::collections::VecDeque;
 
 #[derive(Debug)]
 struct EnergySim {
-    map: Vec<Vec<i32>>,
+    map: Grid<i32>,
 }
 
 #[derive(Debug, Clone)]
@@ -20,72 +23,47 @@ impl Location {
 const FLASHED: i32 = -1;
 
 impl EnergySim {
-    pub fn from_lines<I, T>(lines: I) -> Self
+    pub fn from_lines<I, T>(lines: I) -> parse::Result<Self>
     where
         I: Iterator<Item = T>,
         T: AsRef<str>,
     {
-        let mut map = vec![];
-
-        for line in lines.filter(|l| !l.as_ref().trim().is_empty()) {
-            let row = line
-                .as_ref()
-                .chars()
-                .map(|c| c.to_digit(10).expect("invalid character in map") as i32)
-                .collect();
-            map.push(row);
-        }
-
-        EnergySim { map }
+        let rows = parse::digit_grid(lines)?
+            .into_iter()
+            .map(|row| row.into_iter().map(|e| e as i32).collect())
+            .collect();
+
+        Ok(EnergySim {
+            map: Grid::from_rows(rows),
+        })
     }
 
     fn row_len(&self) -> usize {
-        self.map.len()
+        self.map.height()
     }
 
     fn col_len(&self) -> usize {
-        if let Some(r) = self.map.first() {
-            r.len()
-        } else {
-            0
-        }
+        self.map.width()
     }
 
     fn get(&self, loc: &Location) -> i32 {
-        self.map[loc.row][loc.col]
+        *self.map.get(loc.col, loc.row).unwrap()
     }
 
     fn set(&mut self, loc: &Location, val: i32) {
-        self.map[loc.row][loc.col] = val;
+        self.map.set(loc.col, loc.row, val);
     }
 
     fn neighbours(&self, loc: &Location) -> Vec<(Location, i32)> {
-        let mut result = vec![];
-
-        for r in [
-            loc.row.checked_sub(1),
-            Some(loc.row),
-            loc.row.checked_add(1),
-        ] {
-            for c in [
-                loc.col.checked_sub(1),
-                Some(loc.col),
-                loc.col.checked_add(1),
-            ] {
-                match (r, c) {
-                    (Some(nr), Some(nc)) if nr < self.row_len() && nc < self.col_len() => {
-                        result.push((Location::new(nr, nc), self.map[nr][nc]));
-                    }
-                    (Some(nr), Some(nc)) if nr == loc.row && nc == loc.col => {}
-                    _ => {}
-                }
-            }
-        }
-        result
+        self.map
+            .neighbours8(loc.col, loc.row)
+            .into_iter()
+            .map(|(c, r)| (Location::new(r, c), *self.map.get(c, r).unwrap()))
+            .collect()
     }
 
     pub fn take_step(&mut self) -> usize {
-        for e in self.map.iter_mut().flatten() {
+        for e in self.map.iter_mut() {
             *e += 1;
         }
         let mut count = 0;
@@ -99,7 +77,7 @@ impl EnergySim {
             }
         }
 
-        for e in self.map.iter_mut().flatten() {
+        for e in self.map.iter_mut() {
             if *e == FLASHED {
                 *e = 0;
             }
@@ -107,6 +85,21 @@ impl EnergySim {
         count
     }
 
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(self.col_len(), self.row_len());
+        for (ci, ri) in self.map.positions() {
+            let val = self.get(&Location::new(ri, ci)) as u32;
+            let glyph = std::char::from_digit(val, 10).unwrap_or('*');
+            let cell = if val == 0 {
+                Cell::new(glyph, visual::BLACK).on(visual::WHITE)
+            } else {
+                Cell::new(glyph, visual::heat(val, 9))
+            };
+            frame.set(ci, ri, cell);
+        }
+        frame
+    }
+
     fn propagate(&mut self, loc: &Location) -> usize {
         let mut bfs_que = VecDeque::from([loc.clone()]);
         self.set(loc, FLASHED);
@@ -161,19 +154,46 @@ mod test {
 4846848554
 5283751526
 ";
-        let mut es1 = EnergySim::from_lines(input.lines());
+        let mut es1 = EnergySim::from_lines(input.lines()).unwrap();
         assert_eq!(1656, part1(&mut es1));
 
-        let mut es2 = EnergySim::from_lines(input.lines());
+        let mut es2 = EnergySim::from_lines(input.lines()).unwrap();
         assert_eq!(195, part2(&mut es2));
     }
 }
 
-pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) {
-    let mut es = EnergySim::from_lines(lines);
+pub fn visualize<I: Iterator<Item = String>>(
+    lines: I,
+    part: u8,
+    sink: &mut dyn FrameSink,
+) -> parse::Result<()> {
+    let mut es = EnergySim::from_lines(lines)?;
+    let mut flashes = 0;
+    let mut step = 0;
+    sink.draw(&es.frame().with_caption("step 0".to_string()));
+    loop {
+        step += 1;
+        let count = es.take_step();
+        flashes += count;
+        let caption = format!("step {}, flashes: {}", step, flashes);
+        sink.draw(&es.frame().with_caption(caption));
+
+        let done = match part {
+            1 => step == 100,
+            _ => count == es.row_len() * es.col_len(),
+        };
+        if done {
+            return Ok(());
+        }
+    }
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let mut es = EnergySim::from_lines(lines)?;
     match part {
         1 => println!("part1: {}", part1(&mut es)),
         2 => println!("part2: {}", part2(&mut es)),
         _ => {}
     }
+    Ok(())
 }
@@ -1,22 +1,25 @@
+use crate::parse::{self, ErrorKind, ParseError};
 use std::collections::HashMap;
 
 type CaveId = usize;
 
 struct Cave {
-    name: String,
     neighbors: Vec<CaveId>,
     is_small: bool,
     visit_count: usize,
 }
 
+fn is_small(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_lowercase())
+}
+
 impl Cave {
     fn new(n: &str) -> Self {
         assert!(!n.is_empty());
 
         Cave {
-            name: n.to_string(),
             neighbors: vec![],
-            is_small: n.starts_with(|c: char| c.is_lowercase()),
+            is_small: is_small(n),
             visit_count: 0,
         }
     }
@@ -34,7 +37,7 @@ struct Network {
 }
 
 impl Network {
-    fn from_edges<I, T>(edges: I) -> Self
+    fn from_edges<I, T>(edges: I) -> parse::Result<Self>
     where
         I: Iterator<Item = T>,
         T: AsRef<str>,
@@ -47,12 +50,21 @@ impl Network {
         };
         let mut members = HashMap::new();
 
-        for e in edges {
-            let cnames: Vec<&str> = e.as_ref().trim().split('-').collect();
-            if cnames.len() != 2 {
-                continue;
+        let edges = parse::lines_with(edges, |e| {
+            let (c0, c1) = parse::separated_pair(e, "-", parse::ident, parse::ident)?;
+            // paths could go back and forth between them forever
+            if !is_small(c0) && !is_small(c1) {
+                let kind = ErrorKind::Unexpected {
+                    found: e.trim().to_string(),
+                    expected: "a small cave on either side".to_string(),
+                };
+                return Err(ParseError::new(1 + e.len() - e.trim_start().len(), kind));
             }
-            let c0 = cnames[0];
+            Ok((c0.to_string(), c1.to_string()))
+        })?;
+
+        for (c0, c1) in &edges {
+            let c0 = c0.as_str();
             let c0_id = match members.get(c0) {
                 Some(id) => *id,
                 None => {
@@ -62,7 +74,7 @@ impl Network {
                     id
                 }
             };
-            let c1 = cnames[1];
+            let c1 = c1.as_str();
             let c1_id = match members.get(c1) {
                 Some(id) => *id,
                 None => {
@@ -74,14 +86,20 @@ impl Network {
             };
             nw.caves[c0_id].add_neighbor(c1_id);
             nw.caves[c1_id].add_neighbor(c0_id);
-            if !nw.caves[c0_id].is_small && !nw.caves[c1_id].is_small {
-                panic!("two adjacent large caves in input");
-            }
         }
 
-        nw.start = *members.get("start").expect("no start cave in input");
-        nw.end = *members.get("end").expect("no end cave in input");
-        nw
+        let cave = |name: &str| {
+            members.get(name).copied().ok_or_else(|| {
+                let kind = ErrorKind::Unexpected {
+                    found: "end of input".to_string(),
+                    expected: format!("the {} cave", name),
+                };
+                ParseError::new(1, kind)
+            })
+        };
+        nw.start = cave("start")?;
+        nw.end = cave("end")?;
+        Ok(nw)
     }
 
     fn find_end(&mut self) -> usize {
@@ -135,7 +153,7 @@ b-d
 A-end
 b-end
 ";
-        let mut nw = Network::from_edges(input.lines());
+        let mut nw = Network::from_edges(input.lines()).unwrap();
         assert_eq!(10, nw.find_end());
         nw.allows_two_visits = true;
         assert_eq!(36, nw.find_end());
@@ -155,12 +173,23 @@ kj-sa
 kj-HN
 kj-dc
 ";
-        let mut nw = Network::from_edges(input.lines());
+        let mut nw = Network::from_edges(input.lines()).unwrap();
         assert_eq!(19, nw.find_end());
         nw.allows_two_visits = true;
         assert_eq!(103, nw.find_end());
     }
 
+    #[test]
+    fn bad_networks() {
+        let err = Network::from_edges("start-A\nA-B\nb-end\n".lines()).err();
+        assert_eq!(Some((2, 1)), err.map(|e| (e.line, e.column)));
+        let err = Network::from_edges("start-a\n".lines()).err().unwrap();
+        assert_eq!(
+            "column 1: unexpected 'end of input', expected the end cave",
+            err.to_string()
+        );
+    }
+
     #[test]
     fn puzzle_input3() {
         let input = "
@@ -183,15 +212,15 @@ zg-he
 pj-fs
 start-RW
 ";
-        let mut nw = Network::from_edges(input.lines());
+        let mut nw = Network::from_edges(input.lines()).unwrap();
         assert_eq!(226, nw.find_end());
         nw.allows_two_visits = true;
         assert_eq!(3509, nw.find_end());
     }
 }
 
-pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) {
-    let mut nw = Network::from_edges(lines);
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let mut nw = Network::from_edges(lines)?;
     match part {
         1 => println!("part1: {}", nw.find_end()),
         2 => {
@@ -200,4 +229,5 @@ pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) {
         }
         _ => unreachable!(),
     }
+    Ok(())
 }
@@ -0,0 +1,212 @@
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::visual::{self, Cell, Frame, FrameSink};
+use std::collections::HashSet;
+
+#[derive(Hash, PartialEq, Eq, Clone)]
+struct Point {
+    x: usize,
+    y: usize,
+}
+
+#[derive(Debug)]
+enum Fold {
+    X(usize),
+    Y(usize),
+}
+
+impl Fold {
+    fn parse(line: &str) -> parse::Result<Self> {
+        let instr = parse::prefixed(line, "fold along ")?;
+        let offset = line.len() - instr.len();
+        match parse::key_value(instr, '=', parse::number) {
+            Ok(("x", split)) => Ok(Fold::X(split)),
+            Ok(("y", split)) => Ok(Fold::Y(split)),
+            Ok((axis, _)) => Err(ParseError::new(
+                offset + 1,
+                ErrorKind::Unexpected {
+                    found: axis.to_string(),
+                    expected: "x or y".to_string(),
+                },
+            )),
+            Err(e) => Err(ParseError::new(offset + e.column, e.kind)),
+        }
+    }
+}
+
+fn fold_y(points: &HashSet<Point>, split_y: usize) -> HashSet<Point> {
+    let mut points_seen = HashSet::new();
+    for point in points {
+        if point.y > split_y {
+            if 2 * split_y >= point.y {
+                points_seen.insert(Point {
+                    x: point.x,
+                    y: 2 * split_y - point.y,
+                });
+            }
+        } else {
+            points_seen.insert(point.clone());
+        }
+    }
+    points_seen
+}
+
+fn fold_x(points: &HashSet<Point>, split_x: usize) -> HashSet<Point> {
+    let mut points_seen = HashSet::new();
+    for point in points {
+        if point.x > split_x {
+            if 2 * split_x >= point.x {
+                points_seen.insert(Point {
+                    x: 2 * split_x - point.x,
+                    y: point.y,
+                });
+            }
+        } else {
+            points_seen.insert(point.clone());
+        }
+    }
+    points_seen
+}
+
+fn read_sheet<I, T>(lines: I) -> parse::Result<(HashSet<Point>, Vec<Fold>)>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let sections = parse::sections(lines);
+    let (dots, folds) = match &sections[..] {
+        [dots, folds] => (dots, folds.parse_lines(Fold::parse)?),
+        [dots] => (dots, vec![]),
+        [] => return Err(ParseError::new(1, ErrorKind::Empty)),
+        [_, _, extra, ..] => {
+            let kind = ErrorKind::Unexpected {
+                found: extra.lines[0].clone(),
+                expected: "dots followed by fold instructions".to_string(),
+            };
+            return Err(ParseError::new(1, kind).at_line(extra.first_line));
+        }
+    };
+
+    let points: HashSet<Point> = dots
+        .parse_lines(|l| parse::number_pair(l, ","))?
+        .into_iter()
+        .map(|(x, y)| Point { x, y })
+        .collect();
+    Ok((points, folds))
+}
+
+fn apply_fold(points: &HashSet<Point>, fold: &Fold) -> HashSet<Point> {
+    match *fold {
+        Fold::X(split) => fold_x(points, split),
+        Fold::Y(split) => fold_y(points, split),
+    }
+}
+
+fn sheet_frame(points: &HashSet<Point>) -> Frame {
+    let max_x = points.iter().map(|p| p.x).max().unwrap_or(0);
+    let max_y = points.iter().map(|p| p.y).max().unwrap_or(0);
+    let mut frame =
+        Frame::new(max_x + 1, max_y + 1).with_caption(format!("visible dots: {}", points.len()));
+
+    for x in 0..=max_x {
+        for y in 0..=max_y {
+            frame.set(x, y, Cell::new('.', visual::GREY));
+        }
+    }
+    for point in points {
+        frame.set(point.x, point.y, Cell::new('#', visual::YELLOW));
+    }
+    frame
+}
+
+pub fn solve_impl<I, T>(lines: I) -> parse::Result<usize>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let (mut points, folds) = read_sheet(lines)?;
+    for fold in &folds {
+        points = apply_fold(&points, fold);
+    }
+
+    let max_x = points.iter().map(|p| p.x).max().unwrap();
+    let max_y = points.iter().map(|p| p.y).max().unwrap();
+    let mut plot = vec![vec!['.'; max_x + 1]; max_y + 1];
+
+    for point in &points {
+        plot[point.y][point.x] = '#';
+    }
+
+    for y_line in &plot {
+        println!("{}", y_line.iter().collect::<String>());
+    }
+    Ok(points.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "
+6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+";
+
+    #[test]
+    fn puzzle_example() {
+        assert_eq!(Ok(17), solve_impl(INPUT.lines()));
+    }
+
+    #[test]
+    fn bad_fold() {
+        let err = Fold::parse("fold along z=3").unwrap_err();
+        assert_eq!(
+            "column 12: unexpected 'z', expected x or y",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn fold_visualisation() {
+        let mut frames = visual::Recorder::default();
+        visualize(INPUT.lines().map(String::from), 1, &mut frames).unwrap();
+        assert_eq!(2, frames.frames.len());
+        assert_eq!("visible dots: 17", frames.frames[1].caption);
+    }
+}
+
+pub fn visualize<I: Iterator<Item = String>>(
+    lines: I,
+    _part: u8,
+    sink: &mut dyn FrameSink,
+) -> parse::Result<()> {
+    let (mut points, folds) = read_sheet(lines)?;
+    sink.draw(&sheet_frame(&points));
+    for fold in &folds {
+        points = apply_fold(&points, fold);
+        sink.draw(&sheet_frame(&points));
+    }
+    Ok(())
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, _part: u8) -> parse::Result<()> {
+    solve_impl(lines)?;
+    Ok(())
+}
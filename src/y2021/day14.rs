@@ -0,0 +1,196 @@
+use crate::params::Params;
+use crate::parse::{self, ErrorKind, ParseError};
+use std::collections::HashMap;
+
+type Pair = (char, char);
+
+struct Polymer {
+    // occurrences of every adjacent pair of elements in the chain, the
+    // chain roughly doubles every step so this overflows after ~125 steps.
+    pairs: HashMap<Pair, u128>,
+    last: char,
+    rules: HashMap<Pair, char>,
+}
+
+impl Polymer {
+    fn from_lines<I, T>(lines: I) -> parse::Result<Self>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let sections = parse::sections(lines);
+        let (template, rules) = match &sections[..] {
+            [template, rules] => (template, rules),
+            [] => return Err(ParseError::new(1, ErrorKind::Empty)),
+            [template] => {
+                let kind = ErrorKind::Unexpected {
+                    found: "end of input".to_string(),
+                    expected: "insertion rules".to_string(),
+                };
+                let line = template.first_line + template.lines.len();
+                return Err(ParseError::new(1, kind).at_line(line));
+            }
+            [_, _, extra, ..] => {
+                let kind = ErrorKind::Unexpected {
+                    found: extra.lines[0].clone(),
+                    expected: "a template followed by insertion rules".to_string(),
+                };
+                return Err(ParseError::new(1, kind).at_line(extra.first_line));
+            }
+        };
+
+        // the template is a single line, anything below it before the blank
+        // line is most likely a rule missing its separating blank line.
+        if let Some(extra) = template.lines.get(1) {
+            let kind = ErrorKind::Unexpected {
+                found: extra.clone(),
+                expected: "a blank line after the template".to_string(),
+            };
+            return Err(ParseError::new(1, kind).at_line(template.first_line + 1));
+        }
+        let template: Vec<char> = template.lines[0].trim().chars().collect();
+        let last = *template.last().unwrap();
+        let mut pairs = HashMap::new();
+        for w in template.windows(2) {
+            *pairs.entry((w[0], w[1])).or_insert(0) += 1;
+        }
+
+        let rules = rules.parse_lines(Self::parse_rule)?.into_iter().collect();
+
+        Ok(Polymer { pairs, last, rules })
+    }
+
+    fn parse_rule(line: &str) -> parse::Result<(Pair, char)> {
+        let (pair, elem) = parse::separated_pair(
+            line,
+            "->",
+            |s| Self::elements(s, 2, "a pair of elements"),
+            |s| Self::elements(s, 1, "a single element"),
+        )?;
+        Ok(((pair[0], pair[1]), elem[0]))
+    }
+
+    // one side of a rule, `count` elements long.
+    fn elements(s: &str, count: usize, expected: &str) -> parse::Result<Vec<char>> {
+        let elems: Vec<char> = parse::ident(s)?.chars().collect();
+        if elems.len() != count {
+            let kind = ErrorKind::Unexpected {
+                found: s.trim().to_string(),
+                expected: expected.to_string(),
+            };
+            return Err(ParseError::new(1 + s.len() - s.trim_start().len(), kind));
+        }
+        Ok(elems)
+    }
+
+    fn step(&mut self) {
+        let mut next = HashMap::new();
+        let mut add = |pair: Pair, count: u128| {
+            let entry = next.entry(pair).or_insert(0u128);
+            *entry = entry.checked_add(count).expect("polymer too long to count");
+        };
+        for (&(a, b), &count) in &self.pairs {
+            match self.rules.get(&(a, b)) {
+                Some(&c) => {
+                    add((a, c), count);
+                    add((c, b), count);
+                }
+                None => add((a, b), count),
+            }
+        }
+        self.pairs = next;
+    }
+
+    fn element_counts(&self) -> HashMap<char, u128> {
+        // every element starts exactly one pair, except the last one which
+        // never changes since insertions only happen between elements.
+        let mut counts = HashMap::from([(self.last, 1)]);
+        for (&(a, _), &count) in &self.pairs {
+            *counts.entry(a).or_insert(0) += count;
+        }
+        counts
+    }
+}
+
+fn solve_impl<I, T>(lines: I, steps: usize) -> parse::Result<u128>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let mut polymer = Polymer::from_lines(lines)?;
+    for _ in 0..steps {
+        polymer.step();
+    }
+    let counts = polymer.element_counts();
+    let most = counts.values().max().unwrap();
+    let least = counts.values().min().unwrap();
+    Ok(most - least)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "
+NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C
+";
+
+    #[test]
+    fn puzzle_example() {
+        assert_eq!(Ok(1588), solve_impl(INPUT.lines(), 10));
+        assert_eq!(Ok(2188189693529), solve_impl(INPUT.lines(), 40));
+    }
+
+    #[test]
+    fn bad_input() {
+        let at = |input: &str| {
+            Polymer::from_lines(input.lines())
+                .err()
+                .map(|e| (e.line, e.column))
+        };
+        assert_eq!(Some((3, 1)), at("NNCB\n\nCHX -> B\n"));
+        assert_eq!(Some((3, 7)), at("NNCB\n\nCH -> BC\n"));
+        assert_eq!(Some((2, 1)), at("NNCB\nCH -> B\n\nHH -> N\n"));
+        assert_eq!(Some((2, 1)), at("NNCB\n"));
+    }
+
+    #[test]
+    fn chain_growth() {
+        let mut polymer = Polymer::from_lines(INPUT.lines()).unwrap();
+        for _ in 0..10 {
+            polymer.step();
+        }
+        let counts = polymer.element_counts();
+        assert_eq!(3073, counts.values().sum::<u128>());
+        assert_eq!(Some(&1749), counts.get(&'B'));
+        assert_eq!(Some(&161), counts.get(&'H'));
+    }
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8, params: &Params) -> parse::Result<()> {
+    let default_steps = match part {
+        1 => 10,
+        2 => 40,
+        _ => unreachable!(),
+    };
+    let steps = params.get_or("steps", default_steps)?;
+    println!("part{}: {}", part, solve_impl(lines, steps)?);
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use crate::graph;
+use crate::grid::Grid;
+use crate::parse;
+use crate::visual::{self, Cell, Frame, FrameSink};
+
+// the cave map, tiled `repeat` times in both directions with risk levels
+// increasing by one per tile and wrapping from 9 back to 1.
+struct RiskMap {
+    grid: Grid<u32>,
+}
+
+impl RiskMap {
+    fn new(tile: &[Vec<u32>], repeat: usize) -> Self {
+        let rows = tile.len();
+        let cols = tile.first().map_or(0, |r| r.len());
+        let mut grid = Grid::new(cols * repeat, rows * repeat, 0);
+        for (x, y) in grid.positions().collect::<Vec<_>>() {
+            let val = tile[y % rows][x % cols] + (y / rows + x / cols) as u32;
+            grid.set(x, y, (val - 1) % 9 + 1);
+        }
+        RiskMap { grid }
+    }
+
+    fn risk(&self, x: usize, y: usize) -> u32 {
+        *self.grid.get(x, y).unwrap()
+    }
+
+    // lowest total risk from top-left to bottom-right and the (x, y) cells
+    // visited along the way.
+    fn lowest_risk_path(&self) -> (u32, Vec<(usize, usize)>) {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if width == 0 || height == 0 {
+            return (0, vec![]);
+        }
+        let goal = (width - 1, height - 1);
+        let (total, path) = graph::dijkstra(
+            (0, 0),
+            |&(x, y)| {
+                self.grid
+                    .neighbours4(x, y)
+                    .into_iter()
+                    .map(|(nx, ny)| ((nx, ny), self.risk(nx, ny) as u64))
+            },
+            |&pos| pos == goal,
+        )
+        .expect("bottom right is always reachable");
+        (total as u32, path)
+    }
+
+    fn frame(&self, path: &[(usize, usize)], total: u32) -> Frame {
+        let mut frame = Frame::new(self.grid.width(), self.grid.height())
+            .with_caption(format!("lowest total risk: {}", total));
+        for (x, y) in self.grid.positions() {
+            let risk = self.risk(x, y);
+            let glyph = char::from_digit(risk, 10).unwrap();
+            frame.set(x, y, Cell::new(glyph, visual::heat(risk - 1, 8)));
+        }
+        for &(x, y) in path {
+            let glyph = char::from_digit(self.risk(x, y), 10).unwrap();
+            frame.set(x, y, Cell::new(glyph, visual::BLACK).on(visual::WHITE));
+        }
+        frame
+    }
+}
+
+pub fn part1(grid: &[Vec<u32>]) -> u32 {
+    RiskMap::new(grid, 1).lowest_risk_path().0
+}
+
+pub fn part2(grid: &[Vec<u32>]) -> u32 {
+    RiskMap::new(grid, 5).lowest_risk_path().0
+}
+
+pub fn get_input_grid<I, T>(lines: I) -> parse::Result<Vec<Vec<u32>>>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    parse::digit_grid(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn puzzle_example() {
+        let input = "
+1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581
+";
+
+        let grid = get_input_grid(input.lines()).unwrap();
+        assert_eq!(40, part1(&grid));
+        assert_eq!(315, part2(&grid));
+    }
+
+    #[test]
+    fn part1_extended_9() {
+        let input = "
+91234
+12345
+23456
+34567
+45678
+";
+
+        let grid = get_input_grid(input.lines()).unwrap();
+        assert_eq!(36, part1(&grid));
+    }
+
+    #[test]
+    fn part2_9() {
+        let input = "
+9
+";
+
+        let grid = get_input_grid(input.lines()).unwrap();
+        assert_eq!(36, part2(&grid));
+    }
+
+    #[test]
+    fn part1_going_up() {
+        let input = "
+19999
+19111
+11191
+";
+
+        let grid = get_input_grid(input.lines()).unwrap();
+        assert_eq!(8, part1(&grid));
+    }
+
+    #[test]
+    fn path_matches_risk() {
+        let input = "
+19999
+19111
+11191
+";
+        let grid = get_input_grid(input.lines()).unwrap();
+        let map = RiskMap::new(&grid, 1);
+        let (total, path) = map.lowest_risk_path();
+        assert_eq!(Some(&(0, 0)), path.first());
+        assert_eq!(Some(&(4, 2)), path.last());
+        assert_eq!(
+            total,
+            path[1..].iter().map(|&(x, y)| map.risk(x, y)).sum::<u32>()
+        );
+    }
+}
+
+pub fn visualize<I: Iterator<Item = String>>(
+    lines: I,
+    part: u8,
+    sink: &mut dyn FrameSink,
+) -> parse::Result<()> {
+    let grid = get_input_grid(lines)?;
+    let map = match part {
+        1 => RiskMap::new(&grid, 1),
+        2 => RiskMap::new(&grid, 5),
+        _ => unreachable!(),
+    };
+    let (total, path) = map.lowest_risk_path();
+    sink.draw(&map.frame(&path, total));
+    Ok(())
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let grid = get_input_grid(lines)?;
+    match part {
+        1 => println!("{}", part1(&grid)),
+        2 => println!("{}", part2(&grid)),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use crate::parse;
+
 struct Packet {
     version: u8,
     type_id: u8,
@@ -187,11 +189,12 @@ mod test {
     }
 }
 
-pub fn solve<I: Iterator<Item = String>>(mut lines: I, part: u8) {
-    let input = lines.next().unwrap();
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let input = parse::first_line_with(lines, |l| Ok(l.to_string()))?;
     match part {
         1 => println!("{}", part1(&input)),
         2 => println!("{}", part2(&input)),
         _ => unreachable!(),
     }
+    Ok(())
 }
@@ -0,0 +1,200 @@
+use crate::parse;
+use std::cmp;
+
+#[derive(Debug, PartialEq)]
+struct Target {
+    x1: i64,
+    x2: i64,
+    y1: i64,
+    y2: i64,
+}
+
+fn triangle(n: i64) -> i64 {
+    n * (n + 1) / 2
+}
+
+impl Target {
+    fn parse(line: &str) -> parse::Result<Self> {
+        let range = |s| {
+            parse::separated_pair(s, "..", parse::number::<i64>, parse::number::<i64>)
+                .map(|(a, b)| (cmp::min(a, b), cmp::max(a, b)))
+        };
+        let area = parse::prefixed(line, "target area:")?;
+        let ((_, (x1, x2)), (_, (y1, y2))) = parse::separated_pair(
+            area,
+            ",",
+            |s| parse::key_value(s, '=', range),
+            |s| parse::key_value(s, '=', range),
+        )?;
+        Ok(Target { x1, x2, y1, y2 })
+    }
+
+    fn contains(&self, x: i64, y: i64) -> bool {
+        (self.x1..=self.x2).contains(&x) && (self.y1..=self.y2).contains(&y)
+    }
+
+    // horizontal velocities whose probe comes to a halt within the target
+    // columns, and so keeps falling through them for as long as it likes.
+    fn stalling_vx(&self) -> impl Iterator<Item = i64> + '_ {
+        self.vx_range().filter(move |&vx| {
+            let rest = vx.signum() * triangle(vx.abs());
+            (self.x1..=self.x2).contains(&rest)
+        })
+    }
+
+    // any faster probe overshoots the far edge on its first step.
+    fn vx_range(&self) -> std::ops::RangeInclusive<i64> {
+        cmp::min(self.x1, 0)..=cmp::max(self.x2, 0)
+    }
+
+    // probes launched faster downwards overshoot on the first step. Going
+    // up, a probe retraces its ascent heights on the way down, so with the
+    // target below it crosses y = 0 at -(vy + 1) and with the target above
+    // every ascent height already exceeds vy.
+    fn vy_range(&self) -> Option<std::ops::RangeInclusive<i64>> {
+        let low = cmp::min(self.y1, 0);
+        if self.y2 < 0 {
+            Some(low..=-self.y1 - 1)
+        } else if self.y1 > 0 {
+            Some(low..=self.y2)
+        } else {
+            // target spans the launcher height, a stalled probe returns to
+            // y = 0 inside the target however high it is thrown.
+            None
+        }
+    }
+
+    // the apex of a probe that reaches the target, if it does.
+    fn apex(&self, mut vx: i64, mut vy: i64) -> Option<i64> {
+        let apex = triangle(cmp::max(vy, 0));
+        let (mut x, mut y) = (0, 0);
+        loop {
+            x += vx;
+            y += vy;
+            vx -= vx.signum();
+            vy -= 1;
+
+            if self.contains(x, y) {
+                return Some(apex);
+            }
+            let falling_past = vy < 0 && y < self.y1;
+            let past_x = (vx >= 0 && x > self.x2) || (vx <= 0 && x < self.x1);
+            let stalled_outside = vx == 0 && !(self.x1..=self.x2).contains(&x);
+            if falling_past || past_x || stalled_outside {
+                return None;
+            }
+        }
+    }
+
+    // every velocity hitting the target, None when there are infinitely many.
+    fn velocities(&self) -> Option<Vec<(i64, i64, i64)>> {
+        let vy_range = match self.vy_range() {
+            Some(range) => range,
+            None if self.stalling_vx().next().is_some() => return None,
+            // no probe lingers over the target columns, so a hit has to
+            // happen within the first max |x| steps which bounds vy.
+            None => {
+                let reach = cmp::max(self.x1.abs(), self.x2.abs());
+                cmp::min(self.y1, 0)..=cmp::max(-self.y1 - 1, self.y2 + reach)
+            }
+        };
+
+        let mut result = vec![];
+        for vx in self.vx_range() {
+            for vy in vy_range.clone() {
+                if let Some(apex) = self.apex(vx, vy) {
+                    result.push((vx, vy, apex));
+                }
+            }
+        }
+        Some(result)
+    }
+
+    fn max_apex(&self) -> Option<i64> {
+        // below the launcher with a stalling vx, the fastest upward throw
+        // comes back through y = 0 and lands on the bottom row next step.
+        if self.y2 < 0 && self.stalling_vx().next().is_some() {
+            return Some(triangle(cmp::max(-self.y1 - 1, 0)));
+        }
+        self.velocities()?.iter().map(|&(_, _, apex)| apex).max()
+    }
+}
+
+fn part1(target: &Target) -> Option<i64> {
+    target.max_apex()
+}
+
+fn part2(target: &Target) -> Option<usize> {
+    target.velocities().map(|v| v.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn puzzle_example() {
+        let target = Target::parse("target area: x=20..30, y=-10..-5").unwrap();
+        assert_eq!(
+            Target {
+                x1: 20,
+                x2: 30,
+                y1: -10,
+                y2: -5
+            },
+            target
+        );
+        assert_eq!(Some(45), part1(&target));
+        assert_eq!(Some(112), part2(&target));
+
+        // the search agrees with the closed form
+        let searched = target.velocities().unwrap();
+        assert_eq!(Some(45), searched.iter().map(|v| v.2).max());
+    }
+
+    #[test]
+    fn mirrored_targets() {
+        let left = Target::parse("target area: x=-30..-20, y=-10..-5").unwrap();
+        assert_eq!(Some(45), part1(&left));
+        assert_eq!(Some(112), part2(&left));
+
+        // above the launcher the highest throw hits the top row on the
+        // first step, and slower ones can drop back onto it after the apex
+        let above = Target::parse("target area: x=2..4, y=8..10").unwrap();
+        assert_eq!(Some(55), part1(&above));
+        assert!(above.velocities().unwrap().contains(&(2, 4, 10)));
+        assert!(!above.velocities().unwrap().iter().any(|v| v.1 < 0));
+    }
+
+    #[test]
+    fn spanning_launcher_height() {
+        let around = Target::parse("target area: x=-2..3, y=-4..2").unwrap();
+        assert_eq!(None, part1(&around));
+        assert_eq!(None, part2(&around));
+
+        // nothing stalls over x = 7..8, so only a finite set of throws hits
+        let beside = Target::parse("target area: x=7..8, y=-1..1").unwrap();
+        let hits = beside.velocities().unwrap();
+        assert!(hits.contains(&(7, 0, 0)));
+        let brute_force = (-20..=20)
+            .flat_map(|vx| (-20..=200).map(move |vy| (vx, vy)))
+            .filter(|&(vx, vy)| beside.apex(vx, vy).is_some())
+            .count();
+        assert_eq!(brute_force, hits.len());
+    }
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let target = parse::first_line_with(lines, Target::parse)?;
+    let answer = match part {
+        1 => part1(&target).map(|v| v.to_string()),
+        2 => part2(&target).map(|v| v.to_string()),
+        _ => unreachable!(),
+    };
+    println!(
+        "part{}: {}",
+        part,
+        answer.unwrap_or_else(|| "unbounded, the target spans the launcher".to_string())
+    );
+    Ok(())
+}
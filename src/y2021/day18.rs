@@ -0,0 +1,312 @@
+use crate::parse::{self, ErrorKind, ParseError};
+use log::debug;
+use std::fmt;
+
+type NodeId = usize;
+
+#[derive(Clone, Copy, Debug)]
+enum Node {
+    Regular(u64),
+    Pair(NodeId, NodeId),
+}
+
+// a snailfish number stored as a binary tree in an arena. Reductions leave
+// replaced nodes behind unreferenced; `add` only copies what is reachable.
+#[derive(Clone, Debug)]
+struct Snailfish {
+    nodes: Vec<Node>,
+    root: NodeId,
+}
+
+impl Snailfish {
+    fn parse(line: &str) -> parse::Result<Self> {
+        let mut nodes = vec![];
+        let chars: Vec<char> = line.trim().chars().collect();
+        let offset = line.len() - line.trim_start().len();
+        let mut pos = 0;
+        let root = Self::parse_node(&chars, &mut pos, &mut nodes)
+            .map_err(|e| ParseError::new(offset + e.column, e.kind))?;
+        if pos != chars.len() {
+            let kind = ErrorKind::Unexpected {
+                found: chars[pos..].iter().collect(),
+                expected: "end of line".to_string(),
+            };
+            return Err(ParseError::new(offset + pos + 1, kind));
+        }
+        Ok(Snailfish { nodes, root })
+    }
+
+    fn parse_node(chars: &[char], pos: &mut usize, nodes: &mut Vec<Node>) -> parse::Result<NodeId> {
+        let expect = |pos: &mut usize, c: char| {
+            if chars.get(*pos) == Some(&c) {
+                *pos += 1;
+                Ok(())
+            } else {
+                Err(ParseError::new(
+                    *pos + 1,
+                    ErrorKind::MissingSeparator(c.to_string()),
+                ))
+            }
+        };
+
+        match chars.get(*pos) {
+            Some('[') => {
+                *pos += 1;
+                let left = Self::parse_node(chars, pos, nodes)?;
+                expect(pos, ',')?;
+                let right = Self::parse_node(chars, pos, nodes)?;
+                expect(pos, ']')?;
+                nodes.push(Node::Pair(left, right));
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let start = *pos;
+                while chars.get(*pos).is_some_and(|c| c.is_ascii_digit()) {
+                    *pos += 1;
+                }
+                let digits: String = chars[start..*pos].iter().collect();
+                let value =
+                    parse::number(&digits).map_err(|e| ParseError::new(start + 1, e.kind))?;
+                nodes.push(Node::Regular(value));
+            }
+            Some(&c) => return Err(ParseError::new(*pos + 1, ErrorKind::InvalidDigit(c))),
+            None => return Err(ParseError::new(*pos + 1, ErrorKind::Empty)),
+        }
+        Ok(nodes.len() - 1)
+    }
+
+    fn copy_into(&self, id: NodeId, dst: &mut Vec<Node>) -> NodeId {
+        let node = match self.nodes[id] {
+            Node::Regular(v) => Node::Regular(v),
+            Node::Pair(l, r) => {
+                let l = self.copy_into(l, dst);
+                let r = self.copy_into(r, dst);
+                Node::Pair(l, r)
+            }
+        };
+        dst.push(node);
+        dst.len() - 1
+    }
+
+    fn add(&self, other: &Snailfish) -> Snailfish {
+        let mut nodes = vec![];
+        let left = self.copy_into(self.root, &mut nodes);
+        let right = other.copy_into(other.root, &mut nodes);
+        nodes.push(Node::Pair(left, right));
+        let mut sum = Snailfish {
+            root: nodes.len() - 1,
+            nodes,
+        };
+        debug!("after addition: {}", sum);
+        sum.reduce();
+        sum
+    }
+
+    // regular numbers left to right, each with the pair that holds it and
+    // how many pairs enclose it.
+    fn leaves(&self) -> Vec<(NodeId, Option<NodeId>, usize)> {
+        let mut result = vec![];
+        let mut stack = vec![(self.root, None, 0)];
+        while let Some((id, parent, depth)) = stack.pop() {
+            match self.nodes[id] {
+                Node::Regular(_) => result.push((id, parent, depth)),
+                Node::Pair(l, r) => {
+                    stack.push((r, Some(id), depth + 1));
+                    stack.push((l, Some(id), depth + 1));
+                }
+            }
+        }
+        result
+    }
+
+    fn value(&self, id: NodeId) -> u64 {
+        match self.nodes[id] {
+            Node::Regular(v) => v,
+            Node::Pair(..) => panic!("node {} is not a regular number", id),
+        }
+    }
+
+    fn explode(&mut self) -> bool {
+        let leaves = self.leaves();
+        // the left value of a pair nested inside four pairs is a leaf at
+        // depth 5 whose right sibling is the next leaf.
+        let found = leaves.windows(2).position(|w| {
+            let ((_, p0, d0), (_, p1, _)) = (w[0], w[1]);
+            d0 > 4 && p0 == p1
+        });
+        let i = match found {
+            Some(i) => i,
+            None => return false,
+        };
+
+        let (left, pair, _) = leaves[i];
+        let right = leaves[i + 1].0;
+        let (lval, rval) = (self.value(left), self.value(right));
+        if let Some(&(prev, _, _)) = i.checked_sub(1).and_then(|p| leaves.get(p)) {
+            self.nodes[prev] = Node::Regular(self.value(prev) + lval);
+        }
+        if let Some(&(next, _, _)) = leaves.get(i + 2) {
+            self.nodes[next] = Node::Regular(self.value(next) + rval);
+        }
+        self.nodes[pair.unwrap()] = Node::Regular(0);
+        true
+    }
+
+    fn split(&mut self) -> bool {
+        let target = self
+            .leaves()
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .find(|&id| self.value(id) >= 10);
+        match target {
+            Some(id) => {
+                let v = self.value(id);
+                self.nodes.push(Node::Regular(v / 2));
+                self.nodes.push(Node::Regular(v - v / 2));
+                let n = self.nodes.len();
+                self.nodes[id] = Node::Pair(n - 2, n - 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn reduce(&mut self) {
+        loop {
+            if self.explode() {
+                debug!("after explode:  {}", self);
+            } else if self.split() {
+                debug!("after split:    {}", self);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn magnitude(&self) -> u64 {
+        self.magnitude_of(self.root)
+    }
+
+    fn magnitude_of(&self, id: NodeId) -> u64 {
+        match self.nodes[id] {
+            Node::Regular(v) => v,
+            Node::Pair(l, r) => 3 * self.magnitude_of(l) + 2 * self.magnitude_of(r),
+        }
+    }
+
+    fn fmt_node(&self, id: NodeId, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.nodes[id] {
+            Node::Regular(v) => write!(f, "{}", v),
+            Node::Pair(l, r) => {
+                write!(f, "[")?;
+                self.fmt_node(l, f)?;
+                write!(f, ",")?;
+                self.fmt_node(r, f)?;
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Snailfish {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_node(self.root, f)
+    }
+}
+
+fn read_numbers<I, T>(lines: I) -> parse::Result<Vec<Snailfish>>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    parse::lines_with(lines, Snailfish::parse)
+}
+
+fn sum(numbers: &[Snailfish]) -> Option<Snailfish> {
+    let (first, rest) = numbers.split_first()?;
+    Some(rest.iter().fold(first.clone(), |acc, n| acc.add(n)))
+}
+
+fn part1(numbers: &[Snailfish]) -> u64 {
+    sum(numbers).map_or(0, |n| n.magnitude())
+}
+
+fn part2(numbers: &[Snailfish]) -> u64 {
+    let mut best = 0;
+    for (i, a) in numbers.iter().enumerate() {
+        for (j, b) in numbers.iter().enumerate() {
+            if i != j {
+                best = best.max(a.add(b).magnitude());
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reduced(s: &str) -> String {
+        let mut n = Snailfish::parse(s).unwrap();
+        n.reduce();
+        n.to_string()
+    }
+
+    #[test]
+    fn reduction_steps() {
+        assert_eq!("[[[[0,9],2],3],4]", reduced("[[[[[9,8],1],2],3],4]"));
+        assert_eq!("[7,[6,[5,[7,0]]]]", reduced("[7,[6,[5,[4,[3,2]]]]]"));
+        assert_eq!(
+            "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            reduced("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]")
+        );
+        assert_eq!("[[5,5],[5,6]]", reduced("[10,11]"));
+
+        let a = Snailfish::parse("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap();
+        let b = Snailfish::parse("[1,1]").unwrap();
+        assert_eq!("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", a.add(&b).to_string());
+        assert_eq!(
+            143,
+            Snailfish::parse("[[1,2],[[3,4],5]]").unwrap().magnitude()
+        );
+        assert!(Snailfish::parse("[1,2").is_err());
+        assert_eq!(3, Snailfish::parse("[1;2]").unwrap_err().column);
+        assert_eq!(
+            "column 7: unexpected ']', expected end of line",
+            Snailfish::parse(" [1,2]]").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn puzzle_example() {
+        let input = "
+[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
+";
+        let numbers = read_numbers(input.lines()).unwrap();
+        assert_eq!(
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]",
+            sum(&numbers).unwrap().to_string()
+        );
+        assert_eq!(4140, part1(&numbers));
+        assert_eq!(3993, part2(&numbers));
+    }
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let numbers = read_numbers(lines)?;
+    match part {
+        1 => println!("part1: {}", part1(&numbers)),
+        2 => println!("part2: {}", part2(&numbers)),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
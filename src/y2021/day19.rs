@@ -0,0 +1,449 @@
+use crate::parse::{self, ErrorKind, ParseError};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+type Point = [i32; 3];
+type Rotation = [[i32; 3]; 3];
+
+// scanners sharing this many beacons are considered overlapping
+const MIN_OVERLAP: usize = 12;
+
+fn rotate(r: &Rotation, p: &Point) -> Point {
+    let mut result = [0; 3];
+    for (i, row) in r.iter().enumerate() {
+        result[i] = row[0] * p[0] + row[1] * p[1] + row[2] * p[2];
+    }
+    result
+}
+
+fn determinant(r: &Rotation) -> i32 {
+    r[0][0] * (r[1][1] * r[2][2] - r[1][2] * r[2][1])
+        - r[0][1] * (r[1][0] * r[2][2] - r[1][2] * r[2][0])
+        + r[0][2] * (r[1][0] * r[2][1] - r[1][1] * r[2][0])
+}
+
+// the 24 orientations are the signed permutation matrices that keep
+// handedness, i.e. have a determinant of +1.
+fn rotations() -> Vec<Rotation> {
+    const PERMS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    let mut result = vec![];
+    for perm in PERMS {
+        for signs in 0..8 {
+            let mut r = [[0; 3]; 3];
+            for (row, &col) in perm.iter().enumerate() {
+                r[row][col] = if signs & (1 << row) == 0 { 1 } else { -1 };
+            }
+            if determinant(&r) == 1 {
+                result.push(r);
+            }
+        }
+    }
+    result
+}
+
+fn sub(a: &Point, b: &Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn add(a: &Point, b: &Point) -> Point {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn manhattan(a: &Point, b: &Point) -> i32 {
+    sub(a, b).iter().map(|d| d.abs()).sum()
+}
+
+struct Scanner {
+    beacons: Vec<Point>,
+    // squared distances between every pair of beacons, which do not depend
+    // on the scanner's orientation or position.
+    fingerprint: HashMap<i64, usize>,
+}
+
+impl Scanner {
+    fn new(beacons: Vec<Point>) -> Self {
+        let mut fingerprint = HashMap::new();
+        for (i, a) in beacons.iter().enumerate() {
+            for b in &beacons[i + 1..] {
+                let d = sub(a, b);
+                let dist = d.iter().map(|&v| v as i64 * v as i64).sum::<i64>();
+                *fingerprint.entry(dist).or_insert(0) += 1;
+            }
+        }
+        Scanner {
+            beacons,
+            fingerprint,
+        }
+    }
+
+    // overlapping scanners share the distances between their common beacons
+    fn may_overlap(&self, other: &Scanner) -> bool {
+        let shared: usize = self
+            .fingerprint
+            .iter()
+            .map(|(d, n)| (*n).min(*other.fingerprint.get(d).unwrap_or(&0)))
+            .sum();
+        shared >= MIN_OVERLAP * (MIN_OVERLAP - 1) / 2
+    }
+}
+
+struct BeaconMap {
+    beacons: HashSet<Point>,
+    scanners: Vec<Point>,
+}
+
+// orientation and position of `other` relative to the frame `known` is in.
+fn align(known: &[Point], other: &Scanner, rotations: &[Rotation]) -> Option<(Rotation, Point)> {
+    for r in rotations {
+        let rotated: Vec<Point> = other.beacons.iter().map(|b| rotate(r, b)).collect();
+        let mut offsets = HashMap::new();
+        for a in known {
+            for b in &rotated {
+                let offset = sub(a, b);
+                let count = offsets.entry(offset).or_insert(0);
+                *count += 1;
+                if *count >= MIN_OVERLAP {
+                    return Some((*r, offset));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn build_map(scanners: &[Scanner]) -> Option<BeaconMap> {
+    if scanners.is_empty() {
+        return Some(BeaconMap {
+            beacons: HashSet::new(),
+            scanners: vec![],
+        });
+    }
+    let rotations = rotations();
+    // beacons of every placed scanner, in scanner 0's frame
+    let mut placed: Vec<Option<(Point, Vec<Point>)>> = vec![None; scanners.len()];
+    placed[0] = Some(([0; 3], scanners[0].beacons.clone()));
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(i) = queue.pop_front() {
+        let known = placed[i].as_ref().unwrap().1.clone();
+        for j in 0..scanners.len() {
+            if placed[j].is_some() || !scanners[i].may_overlap(&scanners[j]) {
+                continue;
+            }
+            if let Some((r, offset)) = align(&known, &scanners[j], &rotations) {
+                let beacons = scanners[j]
+                    .beacons
+                    .iter()
+                    .map(|b| add(&rotate(&r, b), &offset))
+                    .collect();
+                placed[j] = Some((offset, beacons));
+                queue.push_back(j);
+            }
+        }
+    }
+
+    let placed: Vec<(Point, Vec<Point>)> = placed.into_iter().collect::<Option<_>>()?;
+    Some(BeaconMap {
+        beacons: placed.iter().flat_map(|(_, b)| b.iter().cloned()).collect(),
+        scanners: placed.into_iter().map(|(s, _)| s).collect(),
+    })
+}
+
+fn read_scanners<I, T>(lines: I) -> parse::Result<Vec<Scanner>>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    parse::sections(lines)
+        .iter()
+        .map(|section| {
+            let beacons = section.parse_lines(|l| {
+                if l.trim_start().starts_with("---") {
+                    return Ok(None);
+                }
+                match parse::number_list::<i32>(l, ',')?[..] {
+                    [x, y, z] => Ok(Some([x, y, z])),
+                    _ => Err(ParseError::new(
+                        1 + l.len() - l.trim_start().len(),
+                        ErrorKind::Unexpected {
+                            found: l.trim().to_string(),
+                            expected: "x,y,z".to_string(),
+                        },
+                    )),
+                }
+            })?;
+            Ok(Scanner::new(beacons.into_iter().flatten().collect()))
+        })
+        .collect()
+}
+
+fn part1(map: &BeaconMap) -> usize {
+    map.beacons.len()
+}
+
+fn part2(map: &BeaconMap) -> i32 {
+    let mut best = 0;
+    for a in &map.scanners {
+        for b in &map.scanners {
+            best = best.max(manhattan(a, b));
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Lcg;
+
+    const INPUT: &str = "
+--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14
+";
+
+    #[test]
+    fn puzzle_example() {
+        let scanners = read_scanners(INPUT.lines()).unwrap();
+        let map = build_map(&scanners).expect("all scanners aligned");
+        assert_eq!(
+            vec![
+                [0, 0, 0],
+                [68, -1246, -43],
+                [1105, -1205, 1229],
+                [-92, -2380, -20],
+                [-20, -1133, 1061],
+            ],
+            map.scanners
+        );
+        assert_eq!(79, part1(&map));
+        assert_eq!(3621, part2(&map));
+    }
+
+    #[test]
+    fn bad_beacons() {
+        let err = read_scanners("--- scanner 0 ---\n1,2\n".lines()).err();
+        assert_eq!(Some((2, 1)), err.map(|e| (e.line, e.column)));
+    }
+
+    #[test]
+    fn orientations() {
+        let rs = rotations();
+        assert_eq!(24, rs.len());
+        let p = [1, 2, 3];
+        let images: HashSet<Point> = rs.iter().map(|r| rotate(r, &p)).collect();
+        assert_eq!(24, images.len());
+    }
+
+    // a cloud of beacons seen by scanners at known positions and
+    // orientations, each reporting what lies within 1000 on every axis.
+    #[test]
+    fn reconstructs_synthetic_scene() {
+        let mut rng = Lcg::new(19);
+        let mut rand = |range: i32| rng.below(2 * range as u64 + 1) as i32 - range;
+        let beacons: Vec<Point> = (0..120)
+            .map(|_| [rand(2000), rand(800), rand(800)])
+            .collect();
+        let positions: Vec<Point> = vec![
+            [0, 0, 0],
+            [600, 50, -40],
+            [1200, -30, 60],
+            [-650, 20, 10],
+            [-1250, -60, -20],
+        ];
+        let rs = rotations();
+
+        let mut input = String::new();
+        for (i, pos) in positions.iter().enumerate() {
+            // report in the scanner's own frame, i.e. undo its orientation
+            let r = rs[(i * 7) % rs.len()];
+            let inverse = [
+                [r[0][0], r[1][0], r[2][0]],
+                [r[0][1], r[1][1], r[2][1]],
+                [r[0][2], r[1][2], r[2][2]],
+            ];
+            input.push_str(&format!("--- scanner {} ---\n", i));
+            for b in &beacons {
+                let rel = sub(b, pos);
+                if rel.iter().all(|v| v.abs() <= 1000) {
+                    let [x, y, z] = rotate(&inverse, &rel);
+                    input.push_str(&format!("{},{},{}\n", x, y, z));
+                }
+            }
+            input.push('\n');
+        }
+
+        let scanners = read_scanners(input.lines()).unwrap();
+        let map = build_map(&scanners).expect("all scanners aligned");
+        assert_eq!(positions, map.scanners);
+
+        let seen: HashSet<Point> = beacons
+            .iter()
+            .filter(|b| {
+                positions
+                    .iter()
+                    .any(|p| sub(b, p).iter().all(|v| v.abs() <= 1000))
+            })
+            .cloned()
+            .collect();
+        assert_eq!(seen, map.beacons);
+        assert_eq!(seen.len(), part1(&map));
+        assert_eq!(2450 + 30 + 80, part2(&map));
+    }
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let scanners = read_scanners(lines)?;
+    let map = match build_map(&scanners) {
+        Some(map) => map,
+        None => {
+            println!("part{}: some scanners do not overlap with the rest", part);
+            return Ok(());
+        }
+    };
+    match part {
+        1 => println!("part1: {}", part1(&map)),
+        2 => println!("part2: {}", part2(&map)),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
@@ -0,0 +1,1152 @@
+use crate::params::Params;
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::visual::{self, Cell, Frame, FrameSink};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::{fmt, fs, iter};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cmd {
+    Forward(u32),
+    Backward(u32),
+    Up(u32),
+    Down(u32),
+}
+
+impl Cmd {
+    fn parse(line: &str) -> parse::Result<Self> {
+        let col = line.len() - line.trim_start().len();
+        let (op, arg) = parse::separated_pair(line.trim(), " ", parse::ident, parse::number::<u32>)
+            .map_err(|e| ParseError::new(col + e.column, e.kind))?;
+        match op {
+            "forward" => Ok(Cmd::Forward(arg)),
+            "backward" => Ok(Cmd::Backward(arg)),
+            "up" => Ok(Cmd::Up(arg)),
+            "down" => Ok(Cmd::Down(arg)),
+            _ => Err(unknown_command(col, op)),
+        }
+    }
+}
+
+impl fmt::Display for Cmd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cmd::Forward(arg) => write!(f, "forward {}", arg),
+            Cmd::Backward(arg) => write!(f, "backward {}", arg),
+            Cmd::Up(arg) => write!(f, "up {}", arg),
+            Cmd::Down(arg) => write!(f, "down {}", arg),
+        }
+    }
+}
+
+fn unknown_command(col: usize, found: &str) -> ParseError {
+    ParseError::new(
+        col + 1,
+        ErrorKind::Unexpected {
+            found: found.to_string(),
+            expected: "forward, backward, up, down, repeat, macro or a macro name".to_string(),
+        },
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Stmt {
+    Cmd(Cmd),
+    Repeat(u32, Vec<Stmt>),
+    Call(String),
+}
+
+// a script of submarine commands, line by line:
+//
+//   # comments run to the end of the line
+//   macro dive {
+//     down 5
+//     forward 1
+//   }
+//   repeat 3 {
+//     dive
+//     backward 1
+//   }
+//
+// macros have to be defined before they are called and can't be
+// redefined, so they can't recurse.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Script {
+    macros: HashMap<String, Vec<Stmt>>,
+    body: Vec<Stmt>,
+}
+
+const KEYWORDS: [&str; 6] = ["forward", "backward", "up", "down", "repeat", "macro"];
+
+// how many commands a script may unroll to. A trajectory keeps a state per
+// command, so a few nested repeats could otherwise fill the memory.
+const MAX_STEPS: u64 = 1_000_000;
+
+fn too_many_steps(col: usize, steps: u64) -> ParseError {
+    ParseError::new(
+        col,
+        ErrorKind::Unexpected {
+            found: format!("{} commands", steps),
+            expected: format!("at most {} commands in all", MAX_STEPS),
+        },
+    )
+}
+
+// the words of `text` with the 1-based column each starts at
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut result = vec![];
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(iter::once((text.len(), ' '))) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                result.push((s + 1, &text[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+enum Block {
+    Top,
+    // the count and the column it is at
+    Repeat(u32, usize),
+    Macro(String),
+}
+
+impl Script {
+    fn parse<I, T>(lines: I) -> parse::Result<Self>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let mut script = Script::default();
+        // commands each macro unrolls to
+        let mut macro_steps = HashMap::new();
+        // open blocks with the line they start on, their statements and the
+        // commands those unroll to
+        let mut stack = vec![(Block::Top, 0, vec![], 0)];
+
+        for (i, line) in lines.enumerate() {
+            let at_line = |e: ParseError| e.at_line(i + 1);
+            let line = line.as_ref();
+            let text = line.split('#').next().unwrap();
+            if text.trim().is_empty() {
+                continue;
+            }
+            let (cols, words): (Vec<usize>, Vec<&str>) = words(text).into_iter().unzip();
+            let col = cols[0] - 1;
+
+            let (stmt, steps) = match words[..] {
+                ["}"] => {
+                    let (block, start, body, steps) = stack.pop().unwrap();
+                    match block {
+                        Block::Top => {
+                            let kind = ErrorKind::Unexpected {
+                                found: "}".to_string(),
+                                expected: "a command outside of any block".to_string(),
+                            };
+                            return Err(at_line(ParseError::new(col + 1, kind)));
+                        }
+                        Block::Repeat(count, count_col) => {
+                            let steps = steps * count as u64;
+                            if steps > MAX_STEPS {
+                                return Err(too_many_steps(count_col, steps).at_line(start));
+                            }
+                            (Stmt::Repeat(count, body), steps)
+                        }
+                        Block::Macro(name) => {
+                            macro_steps.insert(name.clone(), steps);
+                            script.macros.insert(name, body);
+                            continue;
+                        }
+                    }
+                }
+                ["repeat", count, "{"] => {
+                    let count = parse::number(count)
+                        .map_err(|e| ParseError::new(cols[1] - 1 + e.column, e.kind))
+                        .map_err(at_line)?;
+                    stack.push((Block::Repeat(count, cols[1]), i + 1, vec![], 0));
+                    continue;
+                }
+                ["macro", name, "{"] => {
+                    // a name can only mean one thing, or a redefinition
+                    // calling the old body would recurse forever
+                    let defining = stack
+                        .iter()
+                        .any(|(block, ..)| matches!(block, Block::Macro(n) if n == name));
+                    if KEYWORDS.contains(&name) || script.macros.contains_key(name) || defining {
+                        let err = ErrorKind::Unexpected {
+                            found: name.to_string(),
+                            expected: "a new macro name".to_string(),
+                        };
+                        return Err(at_line(ParseError::new(cols[1], err)));
+                    }
+                    stack.push((Block::Macro(name.to_string()), i + 1, vec![], 0));
+                    continue;
+                }
+                [name] if script.macros.contains_key(name) => {
+                    (Stmt::Call(name.to_string()), macro_steps[name])
+                }
+                [op, ..] if !KEYWORDS[..4].contains(&op) => {
+                    return Err(at_line(unknown_command(col, op)));
+                }
+                _ => (Stmt::Cmd(Cmd::parse(text).map_err(at_line)?), 1),
+            };
+            let (_, _, body, total) = stack.last_mut().unwrap();
+            // both are at most MAX_STEPS, the sum can't overflow
+            *total += steps;
+            if *total > MAX_STEPS {
+                return Err(at_line(too_many_steps(col + 1, *total)));
+            }
+            body.push(stmt);
+        }
+
+        let (block, start, body, _) = stack.pop().unwrap();
+        match block {
+            Block::Top => {
+                script.body = body;
+                Ok(script)
+            }
+            // reported where the block starts, the end of input says little
+            _ => {
+                let kind = ErrorKind::Unexpected {
+                    found: "end of input".to_string(),
+                    expected: "'}' closing the block opened here".to_string(),
+                };
+                Err(ParseError::new(1, kind).at_line(start))
+            }
+        }
+    }
+
+    // hands every command to `f` in order, unrolling loops and macros as
+    // they are reached rather than up front. Stops at the first error.
+    fn run<E, F: FnMut(Cmd) -> Result<(), E>>(&self, mut f: F) -> Result<(), E> {
+        self.run_block(&self.body, &mut f)
+    }
+
+    fn run_block<E>(
+        &self,
+        stmts: &[Stmt],
+        f: &mut dyn FnMut(Cmd) -> Result<(), E>,
+    ) -> Result<(), E> {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Cmd(cmd) => f(*cmd)?,
+                Stmt::Repeat(count, body) => {
+                    for _ in 0..*count {
+                        self.run_block(body, f)?;
+                    }
+                }
+                Stmt::Call(name) => self.run_block(&self.macros[name], f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where the submarine is. Everything is signed, it may well fly above the
+/// surface or reverse behind its start.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct State {
+    x: i64,
+    depth: i64,
+    aim: i64,
+}
+
+/// How a command moves the submarine, None when the state would overflow.
+trait Physics {
+    fn apply(&self, state: &State, cmd: Cmd) -> Option<State>;
+}
+
+/// Part 1: up and down change the depth directly.
+struct Direct;
+
+impl Physics for Direct {
+    fn apply(&self, state: &State, cmd: Cmd) -> Option<State> {
+        let mut next = *state;
+        match cmd {
+            Cmd::Forward(arg) => next.x = state.x.checked_add(arg as i64)?,
+            Cmd::Backward(arg) => next.x = state.x.checked_sub(arg as i64)?,
+            Cmd::Up(arg) => next.depth = state.depth.checked_sub(arg as i64)?,
+            Cmd::Down(arg) => next.depth = state.depth.checked_add(arg as i64)?,
+        }
+        Some(next)
+    }
+}
+
+/// Part 2: up and down tilt the submarine, moving changes the depth by aim.
+struct Aim;
+
+impl Physics for Aim {
+    fn apply(&self, state: &State, cmd: Cmd) -> Option<State> {
+        let mut next = *state;
+        match cmd {
+            Cmd::Forward(arg) => {
+                next.x = state.x.checked_add(arg as i64)?;
+                next.depth = state
+                    .depth
+                    .checked_add(state.aim.checked_mul(arg as i64)?)?;
+            }
+            // undoes a forward move, depth included
+            Cmd::Backward(arg) => {
+                next.x = state.x.checked_sub(arg as i64)?;
+                next.depth = state
+                    .depth
+                    .checked_sub(state.aim.checked_mul(arg as i64)?)?;
+            }
+            Cmd::Up(arg) => next.aim = state.aim.checked_sub(arg as i64)?,
+            Cmd::Down(arg) => next.aim = state.aim.checked_add(arg as i64)?,
+        }
+        Some(next)
+    }
+}
+
+// any other rules can be plugged in as a closure
+impl<F: Fn(&State, Cmd) -> Option<State>> Physics for F {
+    fn apply(&self, state: &State, cmd: Cmd) -> Option<State> {
+        self(state, cmd)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Overflow {
+    // a command would take the state out of range
+    Move { state: State, cmd: Cmd },
+    // the final position times depth doesn't fit
+    Product(State),
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Overflow::Move { state, cmd } => {
+                write!(f, "submarine state {:?} overflows on {}", state, cmd)
+            }
+            Overflow::Product(state) => {
+                write!(f, "x {} times depth {} overflows", state.x, state.depth)
+            }
+        }
+    }
+}
+
+struct Submarine<P> {
+    physics: P,
+    state: State,
+}
+
+impl<P: Physics> Submarine<P> {
+    fn new(physics: P) -> Self {
+        Submarine {
+            physics,
+            state: State::default(),
+        }
+    }
+
+    fn execute(&mut self, cmd: Cmd) -> Result<(), Overflow> {
+        let state = self.state;
+        self.state = self
+            .physics
+            .apply(&state, cmd)
+            .ok_or(Overflow::Move { state, cmd })?;
+        Ok(())
+    }
+
+    fn run(&mut self, script: &Script) -> Result<(), Overflow> {
+        script.run(|cmd| self.execute(cmd))
+    }
+
+    // like `run`, keeping the state after every command
+    fn trace(&mut self, script: &Script) -> Result<Trajectory, Overflow> {
+        let mut trajectory = Trajectory {
+            start: self.state,
+            steps: vec![],
+        };
+        script.run(|cmd| {
+            self.execute(cmd)?;
+            trajectory.steps.push((cmd, self.state));
+            Ok(())
+        })?;
+        Ok(trajectory)
+    }
+
+    // horizontal position times depth, as the puzzle asks for
+    fn product(&self) -> Result<i64, Overflow> {
+        let state = self.state;
+        state
+            .x
+            .checked_mul(state.depth)
+            .ok_or(Overflow::Product(state))
+    }
+}
+
+/// Where the submarine went, one state per command.
+#[derive(Debug)]
+struct Trajectory {
+    start: State,
+    steps: Vec<(Cmd, State)>,
+}
+
+// the first time the submarine got deepest, or shallowest. Step 0 is the
+// start, before any command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Extreme {
+    step: usize,
+    cmd: Option<Cmd>,
+    state: State,
+}
+
+impl fmt::Display for Extreme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at x {}", self.state.depth, self.state.x)?;
+        match self.cmd {
+            Some(cmd) => write!(f, ", step {}: {}", self.step, cmd),
+            None => write!(f, ", the start"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Summary {
+    commands: usize,
+    end: State,
+    deepest: Extreme,
+    shallowest: Extreme,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "commands: {}, end: x {}, depth {}, aim {}",
+            self.commands, self.end.x, self.end.depth, self.end.aim
+        )?;
+        writeln!(f, "max depth: {}", self.deepest)?;
+        write!(f, "min depth: {}", self.shallowest)
+    }
+}
+
+impl Trajectory {
+    fn states(&self) -> impl Iterator<Item = State> + '_ {
+        iter::once(self.start).chain(self.steps.iter().map(|&(_, state)| state))
+    }
+
+    fn end(&self) -> State {
+        self.states().last().unwrap()
+    }
+
+    fn extreme(&self, beats: fn(i64, i64) -> bool) -> Extreme {
+        let mut best = Extreme {
+            step: 0,
+            cmd: None,
+            state: self.start,
+        };
+        for (i, &(cmd, state)) in self.steps.iter().enumerate() {
+            if beats(state.depth, best.state.depth) {
+                best = Extreme {
+                    step: i + 1,
+                    cmd: Some(cmd),
+                    state,
+                };
+            }
+        }
+        best
+    }
+
+    fn summary(&self) -> Summary {
+        Summary {
+            commands: self.steps.len(),
+            end: self.end(),
+            deepest: self.extreme(|a, b| a > b),
+            shallowest: self.extreme(|a, b| a < b),
+        }
+    }
+
+    // leftmost x, rightmost x, shallowest and deepest depth
+    fn bounds(&self) -> (i64, i64, i64, i64) {
+        self.states().fold(
+            (i64::MAX, i64::MIN, i64::MAX, i64::MIN),
+            |(left, right, top, bottom), s| {
+                (
+                    left.min(s.x),
+                    right.max(s.x),
+                    top.min(s.depth),
+                    bottom.max(s.depth),
+                )
+            },
+        )
+    }
+
+    fn to_csv(&self) -> String {
+        let mut out = "step,command,x,depth,aim\n".to_string();
+        let start = self.start;
+        out.push_str(&format!("0,,{},{},{}\n", start.x, start.depth, start.aim));
+        for (i, (cmd, s)) in self.steps.iter().enumerate() {
+            out.push_str(&format!(
+                "{},{},{},{},{}\n",
+                i + 1,
+                cmd,
+                s.x,
+                s.depth,
+                s.aim
+            ));
+        }
+        out
+    }
+
+    // depth grows downwards in SVG too, so states map to points as they are.
+    // The two axes can differ by orders of magnitude, hence the stretching.
+    fn to_svg(&self) -> String {
+        let (left, right, top, bottom) = self.bounds();
+        let (width, height) = ((right - left).max(1), (bottom - top).max(1));
+        let points: Vec<String> = self
+            .states()
+            .map(|s| format!("{},{}", s.x, s.depth))
+            .collect();
+        let deepest = self.summary().deepest;
+        let stroke = "vector-effect=\"non-scaling-stroke\"";
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"800\" height=\"400\" \
+             viewBox=\"{} {} {} {}\" preserveAspectRatio=\"none\">\n",
+            left, top, width, height
+        );
+        out.push_str(&format!(
+            "<line x1=\"{}\" y1=\"0\" x2=\"{}\" y2=\"0\" stroke=\"steelblue\" {}/>\n",
+            left, right, stroke
+        ));
+        out.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"black\" {}/>\n",
+            points.join(" "),
+            stroke
+        ));
+        // a round cap on an empty path stays a dot however the axes stretch
+        out.push_str(&format!(
+            "<path d=\"M {} {} h 0\" stroke=\"red\" stroke-width=\"8\" \
+             stroke-linecap=\"round\" {}><title>max depth: {}</title></path>\n",
+            deepest.state.x, deepest.state.depth, stroke, deepest
+        ));
+        out.push_str("</svg>\n");
+        out
+    }
+
+    // the depth profile squeezed into `width` by `height` cells, the surface
+    // drawn as waves and the deepest point marked.
+    fn profile(&self, width: usize, height: usize) -> Frame {
+        let (left, right, top, bottom) = self.bounds();
+        let scale = |v: i64, min: i64, max: i64, cells: usize| {
+            let range = (max as i128 - min as i128).max(1);
+            ((v as i128 - min as i128) * (cells as i128 - 1) / range) as usize
+        };
+        let mut frame = Frame::new(width, height);
+        if (top..=bottom).contains(&0) {
+            let y = scale(0, top, bottom, height);
+            for x in 0..width {
+                frame.set(x, y, Cell::new('~', visual::GREY));
+            }
+        }
+        for s in self.states() {
+            let (x, y) = (
+                scale(s.x, left, right, width),
+                scale(s.depth, top, bottom, height),
+            );
+            frame.set(x, y, Cell::new('*', visual::WHITE));
+        }
+        let deepest = self.summary().deepest.state;
+        frame.set(
+            scale(deepest.x, left, right, width),
+            scale(deepest.depth, top, bottom, height),
+            Cell::new('v', visual::YELLOW),
+        );
+        frame.with_caption(format!("x {}..{}, depth {}..{}", left, right, top, bottom))
+    }
+}
+
+fn execute_commands<P: Physics>(script: &Script, physics: P) -> Result<i64, Overflow> {
+    let mut submarine = Submarine::new(physics);
+    submarine.run(script)?;
+    submarine.product()
+}
+
+fn trace_commands<P: Physics>(script: &Script, physics: P) -> Result<(i64, Trajectory), Overflow> {
+    let mut submarine = Submarine::new(physics);
+    let trajectory = submarine.trace(script)?;
+    Ok((submarine.product()?, trajectory))
+}
+
+/// Depths the submarine has to stay within all the way.
+#[derive(Clone, Copy, Debug)]
+struct Limits {
+    min_depth: i64,
+    max_depth: i64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            min_depth: i64::MIN,
+            max_depth: i64::MAX,
+        }
+    }
+}
+
+impl Limits {
+    fn allow(&self, depth: i64) -> bool {
+        (self.min_depth..=self.max_depth).contains(&depth)
+    }
+}
+
+// physics that can tell how to get somewhere, not only where commands lead
+trait Plan: Physics {
+    // a shortest sequence of forward, up and down commands taking the
+    // submarine from the surface to `x`, `depth`. None when it can't get
+    // there. Arguments above u32::MAX take a few commands instead of one.
+    fn plan(&self, x: i64, depth: i64) -> Option<Vec<Cmd>>;
+}
+
+// `amount` as commands of one kind or the other depending on its sign
+fn split(amount: i64, positive: fn(u32) -> Cmd, negative: fn(u32) -> Cmd) -> Vec<Cmd> {
+    let kind = if amount < 0 { negative } else { positive };
+    let mut left = amount.unsigned_abs();
+    let mut cmds = vec![];
+    while left > 0 {
+        let arg = left.min(u32::MAX as u64);
+        cmds.push(kind(arg as u32));
+        left -= arg;
+    }
+    cmds
+}
+
+impl Plan for Direct {
+    fn plan(&self, x: i64, depth: i64) -> Option<Vec<Cmd>> {
+        if x < 0 {
+            return None;
+        }
+        let mut cmds = split(x, Cmd::Forward, Cmd::Backward);
+        cmds.extend(split(depth, Cmd::Down, Cmd::Up));
+        Some(cmds)
+    }
+}
+
+// largest divisor of `n` below `bound`, pairing divisors up to the square
+// root so a large bound or a large `n` takes at most sqrt(n) tries.
+fn divisor_below(n: i64, bound: i64) -> Option<i64> {
+    let (n, bound) = (n.unsigned_abs(), bound.max(0) as u64);
+    (1..)
+        .take_while(|f| f * f <= n)
+        .filter(|f| n % f == 0)
+        .flat_map(|f| [f, n / f])
+        .filter(|&f| f < bound)
+        .max()
+        .map(|f| f as i64)
+}
+
+impl Plan for Aim {
+    // only forward moves change the depth, by aim each. A plan ends with a
+    // forward move, so with at most three commands it is one of
+    //
+    //   forward x                       depth 0
+    //   down a, forward x               depth a * x
+    //   forward x - f, down a, forward f  depth a * f
+    //
+    // and as 1 divides anything the last one always works for x >= 2.
+    // The largest f keeps the aim small.
+    fn plan(&self, x: i64, depth: i64) -> Option<Vec<Cmd>> {
+        // legs of aim and distance, the aim only changes in between
+        let legs = if x < 0 || (x == 0 && depth != 0) {
+            return None;
+        } else if depth == 0 {
+            vec![(0, x)]
+        } else if depth % x == 0 {
+            vec![(depth / x, x)]
+        } else {
+            let f = divisor_below(depth, x)?;
+            vec![(0, x - f), (depth / f, f)]
+        };
+        let mut cmds = vec![];
+        let mut aim = 0i64;
+        for (leg_aim, distance) in legs {
+            cmds.extend(split(leg_aim.checked_sub(aim)?, Cmd::Down, Cmd::Up));
+            cmds.extend(split(distance, Cmd::Forward, Cmd::Backward));
+            aim = leg_aim;
+        }
+        Some(cmds)
+    }
+}
+
+/// The puzzle's rules picked by name, `direct` for part 1 and `aim` for
+/// part 2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rules {
+    Direct,
+    Aim,
+}
+
+impl Rules {
+    // `-P physics`, or the part's own rules
+    fn select(part: u8, params: &Params) -> parse::Result<Self> {
+        let default = if part == 1 { Rules::Direct } else { Rules::Aim };
+        params.get_or("physics", default)
+    }
+}
+
+impl FromStr for Rules {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> parse::Result<Self> {
+        match s {
+            "direct" => Ok(Rules::Direct),
+            "aim" => Ok(Rules::Aim),
+            _ => Err(ParseError::new(
+                1,
+                ErrorKind::Unexpected {
+                    found: s.to_string(),
+                    expected: "direct or aim".to_string(),
+                },
+            )),
+        }
+    }
+}
+
+impl Physics for Rules {
+    fn apply(&self, state: &State, cmd: Cmd) -> Option<State> {
+        match self {
+            Rules::Direct => Direct.apply(state, cmd),
+            Rules::Aim => Aim.apply(state, cmd),
+        }
+    }
+}
+
+impl Plan for Rules {
+    fn plan(&self, x: i64, depth: i64) -> Option<Vec<Cmd>> {
+        match self {
+            Rules::Direct => Direct.plan(x, depth),
+            Rules::Aim => Aim.plan(x, depth),
+        }
+    }
+}
+
+// neither physics plans a detour, each move heads straight from the surface
+// towards the target depth. So no limit forces a longer plan, the target
+// is either within them or out of reach.
+fn plan_route<P: Plan>(physics: P, x: i64, depth: i64, limits: &Limits) -> Option<Vec<Cmd>> {
+    if !limits.allow(0) || !limits.allow(depth) {
+        return None;
+    }
+    let cmds = physics.plan(x, depth)?;
+    // running the plan is cheap, make sure it gets there
+    let script = Script {
+        body: cmds.iter().map(|&cmd| Stmt::Cmd(cmd)).collect(),
+        ..Script::default()
+    };
+    let trajectory = Submarine::new(physics)
+        .trace(&script)
+        .unwrap_or_else(|e| panic!("{}", e));
+    let end = trajectory.end();
+    assert!(
+        (end.x, end.depth) == (x, depth) && trajectory.states().all(|s| limits.allow(s.depth)),
+        "the plan for x {}, depth {} ends at {:?}",
+        x,
+        depth,
+        end
+    );
+    Some(cmds)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn script<I: Iterator<Item = T>, T: AsRef<str>>(lines: I) -> Script {
+        Script::parse(lines).unwrap()
+    }
+
+    const SCRIPT: &str = "
+# dive twice, then come back a bit
+macro dive {
+  down 5      # steeper
+  forward 1
+}
+repeat 2 {
+  dive
+  repeat 2 {
+    forward 3
+  }
+}
+backward 4
+";
+
+    #[test]
+    fn script_language() {
+        let script = Script::parse(SCRIPT.lines()).unwrap();
+        let mut cmds = vec![];
+        script
+            .run(|cmd| {
+                cmds.push(cmd);
+                Ok::<_, ()>(())
+            })
+            .unwrap();
+        let dive = [Cmd::Down(5), Cmd::Forward(1)];
+        let leg = [&dive[..], &[Cmd::Forward(3), Cmd::Forward(3)]].concat();
+        assert_eq!([&leg[..], &leg, &[Cmd::Backward(4)]].concat(), cmds);
+
+        // 14 - 4 forward at a depth of 10
+        assert_eq!(Ok(100), execute_commands(&script, Direct));
+        // 7 forward at aim 5, then 7 at aim 10 and 4 back at 10
+        assert_eq!(Ok(10 * (35 + 70 - 40)), execute_commands(&script, Aim));
+    }
+
+    #[test]
+    fn script_errors() {
+        let err = Script::parse("forward 1\n  sideways 2\n".lines()).unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        assert_eq!(
+            "line 2, column 3: unexpected 'sideways', expected forward, backward, up, down, \
+             repeat, macro or a macro name",
+            err.to_string()
+        );
+
+        // macros are only known after their definition
+        let err = Script::parse("dive\nmacro dive {\nup 1\n}".lines()).unwrap_err();
+        assert_eq!(1, err.line);
+
+        let err = Script::parse("repeat 2 {\nforward 1\n".lines()).unwrap_err();
+        assert_eq!(1, err.line);
+        assert!(matches!(err.kind, ErrorKind::Unexpected { .. }));
+        let err = Script::parse("forward 1\n}\n".lines()).unwrap_err();
+        assert_eq!(
+            "line 2, column 1: unexpected '}', expected a command outside of any block",
+            err.to_string()
+        );
+        let err = Script::parse("repeat x {\n}\n".lines()).unwrap_err();
+        assert_eq!((1, 8), (err.line, err.column));
+        assert!(Script::parse("forward -1".lines()).is_err());
+
+        // the count's column, not the 'e' inside "repeat"
+        let err = Script::parse("  repeat e {\n}\n".lines()).unwrap_err();
+        assert_eq!((1, 10), (err.line, err.column));
+
+        // a redefinition calling the old name used to recurse forever
+        let redefined = "macro a {\ndown 1\n}\nmacro a {\na\nforward 1\n}\na\n";
+        let err = Script::parse(redefined.lines()).unwrap_err();
+        assert_eq!((4, 7), (err.line, err.column));
+        assert_eq!(
+            "line 4, column 7: unexpected 'a', expected a new macro name",
+            err.to_string()
+        );
+        let err = Script::parse("macro a {\nmacro a {\n}\n}\n".lines()).unwrap_err();
+        assert_eq!((2, 7), (err.line, err.column));
+        let err = Script::parse("macro forward {\nup 1\n}\n".lines()).unwrap_err();
+        assert_eq!((1, 7), (err.line, err.column));
+
+        // nested repeats and calls multiply, reported where the limit is crossed
+        let nested = "repeat 1000 {\n  repeat 1001 {\n    up 1\n  }\n}\n";
+        let err = Script::parse(nested.lines()).unwrap_err();
+        assert_eq!((1, 8), (err.line, err.column));
+        assert_eq!(
+            "line 1, column 8: unexpected '1001000 commands', expected at most 1000000 \
+             commands in all",
+            err.to_string()
+        );
+        let calls = "macro a {\nrepeat 1000000 {\nup 1\n}\n}\na\na\n";
+        let err = Script::parse(calls.lines()).unwrap_err();
+        assert_eq!((7, 1), (err.line, err.column));
+        assert!(Script::parse("repeat 1000 {\nrepeat 1000 {\nup 1\n}\n}\n".lines()).is_ok());
+    }
+
+    #[test]
+    fn puzzle_example() {
+        let lines = [
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ];
+        assert_eq!(Ok(150), execute_commands(&script(lines.iter()), Direct));
+        assert_eq!(Ok(900), execute_commands(&script(lines.iter()), Aim));
+    }
+
+    #[test]
+    fn simple_seqs() {
+        let lines = ["forward 2", "down 4", "up 2"];
+        assert_eq!(Ok(4), execute_commands(&script(lines.iter()), Direct));
+        assert_eq!(Ok(0), execute_commands(&script(lines.iter()), Aim));
+
+        let flying = ["forward 2", "down 4", "up 6"];
+        assert_eq!(Ok(-4), execute_commands(&script(flying.iter()), Direct));
+        let forwards = ["down 2", "forward 100", "up 1", "forward 100"];
+        assert_eq!(Ok(60000), execute_commands(&script(forwards.iter()), Aim));
+    }
+
+    #[test]
+    fn rules() {
+        let params = Params::from_args(["physics=aim"].into_iter()).unwrap();
+        assert_eq!(Rules::Aim, Rules::select(1, &params).unwrap());
+        assert_eq!(Rules::Direct, Rules::select(1, &Params::default()).unwrap());
+        assert_eq!(Rules::Aim, Rules::select(2, &Params::default()).unwrap());
+        assert!("sideways".parse::<Rules>().is_err());
+        assert_eq!(
+            Some(vec![Cmd::Down(4), Cmd::Forward(15)]),
+            plan_route(Rules::Aim, 15, 60, &Limits::default())
+        );
+    }
+
+    #[test]
+    fn physics() {
+        // aiming upwards used to underflow
+        let climb = ["up 5", "forward 2"];
+        assert_eq!(Ok(-20), execute_commands(&script(climb.iter()), Aim));
+        assert_eq!(Ok(-10), execute_commands(&script(climb.iter()), Direct));
+
+        // a current pushing the submarine down by one on every move
+        let drift = |state: &State, cmd: Cmd| {
+            let mut next = Direct.apply(state, cmd)?;
+            next.depth = next.depth.checked_add(1)?;
+            Some(next)
+        };
+        assert_eq!(
+            Ok(2 * 5),
+            execute_commands(&script(["forward 2", "down 3"].iter()), drift)
+        );
+
+        let mut submarine = Submarine::new(Aim);
+        submarine
+            .run(&Script::parse(["down 1000000000"].iter()).unwrap())
+            .unwrap();
+        let dive = Script::parse(["repeat 3 {", "forward 4000000000", "}"].iter()).unwrap();
+        let err = submarine.run(&dive).unwrap_err();
+        assert!(matches!(
+            err,
+            Overflow::Move { state, cmd: Cmd::Forward(4000000000) } if state.x == 8000000000
+        ));
+
+        let err = execute_commands(
+            &script(
+                [
+                    "forward 4000000000",
+                    "down 4000000000",
+                    "repeat 2 {",
+                    "forward 2000000000",
+                    "}",
+                ]
+                .iter(),
+            ),
+            Direct,
+        );
+        assert_eq!(
+            "x 8000000000 times depth 4000000000 overflows",
+            err.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn trajectory() {
+        let example = [
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ];
+        let script = Script::parse(example.iter()).unwrap();
+        let trajectory = Submarine::new(Direct).trace(&script).unwrap();
+        let summary = trajectory.summary();
+        assert_eq!(6, summary.commands);
+        assert_eq!(
+            (5, Some(Cmd::Down(8))),
+            (summary.deepest.step, summary.deepest.cmd)
+        );
+        assert_eq!((0, None), (summary.shallowest.step, summary.shallowest.cmd));
+        assert_eq!("10 at x 13, step 5: down 8", summary.deepest.to_string());
+
+        let (product, trajectory) = trace_commands(&script, Aim).unwrap();
+        assert_eq!(900, product);
+        assert_eq!((15, 60, 10), {
+            let end = trajectory.end();
+            (end.x, end.depth, end.aim)
+        });
+        assert_eq!(6, trajectory.summary().deepest.step);
+        let csv = trajectory.to_csv();
+        assert_eq!(
+            vec!["step,command,x,depth,aim", "0,,0,0,0", "1,forward 5,5,0,0"],
+            csv.lines().take(3).collect::<Vec<_>>()
+        );
+        assert_eq!("6,forward 2,15,60,10", csv.lines().last().unwrap());
+        assert!(trajectory
+            .to_svg()
+            .contains("points=\"0,0 5,0 5,0 13,40 13,40 13,40 15,60\""));
+
+        let profile = trajectory.profile(4, 3).to_text();
+        assert_eq!("**~~\n  *\n   v\nx 0..15, depth 0..60\n", profile);
+    }
+
+    #[test]
+    fn planner() {
+        let limits = Limits::default();
+        assert_eq!(
+            Some(vec![Cmd::Down(4), Cmd::Forward(15)]),
+            plan_route(Aim, 15, 60, &limits)
+        );
+        assert_eq!(
+            Some(vec![Cmd::Forward(8), Cmd::Down(1), Cmd::Forward(7)]),
+            plan_route(Aim, 15, 7, &limits)
+        );
+        assert_eq!(
+            Some(vec![Cmd::Forward(15), Cmd::Up(60)]),
+            plan_route(Direct, 15, -60, &limits)
+        );
+        assert_eq!(Some(vec![]), plan_route(Aim, 0, 0, &limits));
+        assert_eq!(None, plan_route(Aim, 0, 3, &limits));
+        assert_eq!(None, plan_route(Direct, -1, 3, &limits));
+
+        // too steep for a single command
+        let deep = plan_route(Aim, 1, 5_000_000_000, &limits).unwrap();
+        assert_eq!(
+            vec![Cmd::Down(u32::MAX), Cmd::Down(705032705), Cmd::Forward(1)],
+            deep
+        );
+
+        let shallow = Limits {
+            min_depth: 0,
+            max_depth: 60,
+        };
+        assert!(plan_route(Aim, 15, 60, &shallow).is_some());
+        assert_eq!(None, plan_route(Aim, 15, 61, &shallow));
+        assert_eq!(None, plan_route(Direct, 15, -1, &shallow));
+    }
+
+    #[test]
+    fn shortest_plans() {
+        // every target reachable with up to three commands of arguments up
+        // to 6, with the fewest commands it takes
+        let mut fewest = HashMap::new();
+        let args = 1..=6;
+        let cmds: Vec<Cmd> = args
+            .clone()
+            .flat_map(|a| [Cmd::Forward(a), Cmd::Up(a), Cmd::Down(a)])
+            .collect();
+        let mut frontier = vec![State::default()];
+        fewest.insert((0, 0), 0);
+        for len in 1..=3 {
+            let mut next = vec![];
+            for state in &frontier {
+                for &cmd in &cmds {
+                    let state = Aim.apply(state, cmd).unwrap();
+                    fewest.entry((state.x, state.depth)).or_insert(len);
+                    next.push(state);
+                }
+            }
+            frontier = next;
+        }
+        for (&(x, depth), &len) in &fewest {
+            let plan = plan_route(Aim, x, depth, &Limits::default()).unwrap();
+            // longer arguments can only shorten the plan
+            assert!(plan.len() <= len, "x {}, depth {}: {:?}", x, depth, plan);
+        }
+        for x in 2..6 {
+            for depth in -20..20 {
+                let plan = plan_route(Aim, x, depth, &Limits::default()).unwrap();
+                assert!(plan.len() <= 3);
+            }
+        }
+    }
+}
+
+const PROFILE_WIDTH: usize = 72;
+const PROFILE_HEIGHT: usize = 20;
+
+// the file extension picks the format: .csv, .svg or a .txt depth profile
+fn export(trajectory: &Trajectory, path: &str) -> Result<(), String> {
+    let contents = match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("csv") => trajectory.to_csv(),
+        Some("svg") => trajectory.to_svg(),
+        Some("txt") => trajectory.profile(PROFILE_WIDTH, PROFILE_HEIGHT).to_text(),
+        _ => return Err(format!("{} must end in .csv, .svg or .txt", path)),
+    };
+    fs::write(path, contents).map_err(|e| format!("cannot write {}: {}", path, e))
+}
+
+pub fn visualize<I>(
+    lines: I,
+    part: u8,
+    params: &Params,
+    sink: &mut dyn FrameSink,
+) -> parse::Result<()>
+where
+    I: Iterator<Item = String>,
+{
+    let rules = Rules::select(part, params)?;
+    let script = Script::parse(lines)?;
+    let trajectory = match trace_commands(&script, rules) {
+        Ok((_, trajectory)) => trajectory,
+        Err(overflow) => {
+            println!("part{}: {}", part, overflow);
+            return Ok(());
+        }
+    };
+    let deepest = trajectory.summary().deepest;
+    let frame = trajectory.profile(PROFILE_WIDTH, PROFILE_HEIGHT);
+    let caption = format!("{}, max depth: {}", frame.caption, deepest);
+    sink.draw(&frame.with_caption(caption));
+    Ok(())
+}
+
+// prints a script reaching `-P target=x,depth`, kept between `-P
+// min_depth` and `-P max_depth` when given
+fn print_plan((x, depth): (i64, i64), part: u8, params: &Params) -> parse::Result<()> {
+    let limits = Limits {
+        min_depth: params.get_or("min_depth", i64::MIN)?,
+        max_depth: params.get_or("max_depth", i64::MAX)?,
+    };
+    match plan_route(Rules::select(part, params)?, x, depth, &limits) {
+        Some(cmds) => {
+            println!("# {} commands to x {}, depth {}", cmds.len(), x, depth);
+            for cmd in cmds {
+                println!("{}", cmd);
+            }
+        }
+        None => println!("x {}, depth {} can't be reached", x, depth),
+    }
+    Ok(())
+}
+
+// `-P physics=direct` or `-P physics=aim` overrides the part's own rules.
+// `-P trajectory=path` also saves where the submarine went and prints a
+// summary of the dive. `-P target=x,depth` ignores the input and plans a
+// way there instead.
+pub fn solve<I>(lines: I, part: u8, params: &Params) -> parse::Result<()>
+where
+    I: Iterator<Item = String>,
+{
+    if let Some(target) = params.get_with("target", |v| parse::number_pair(v, ","))? {
+        return print_plan(target, part, params);
+    }
+    let rules = Rules::select(part, params)?;
+    let script = Script::parse(lines)?;
+    if let Some(path) = params.get::<String>("trajectory")? {
+        let (product, trajectory) = match trace_commands(&script, rules) {
+            Ok(traced) => traced,
+            Err(overflow) => {
+                println!("part{}: {}", part, overflow);
+                return Ok(());
+            }
+        };
+        println!("part{}: {}", part, product);
+        println!("{}", trajectory.summary());
+        match export(&trajectory, &path) {
+            Ok(()) => println!("saved {}", path),
+            Err(e) => println!("trajectory not saved, {}", e),
+        }
+        return Ok(());
+    }
+    match execute_commands(&script, rules) {
+        Ok(product) => println!("part{}: {}", part, product),
+        Err(overflow) => println!("part{}: {}", part, overflow),
+    }
+    Ok(())
+}
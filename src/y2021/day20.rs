@@ -0,0 +1,245 @@
+use crate::grid::Grid;
+use crate::params::Params;
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::visual::{self, Cell, Frame, FrameSink};
+
+// every output pixel looks up a 3x3 neighbourhood in the algorithm
+const ALGORITHM_LEN: usize = 512;
+
+// the finite lit region of an infinite image, everything outside `pixels`
+// has the `background` value.
+struct Image {
+    pixels: Grid<bool>,
+    background: bool,
+}
+
+impl Image {
+    fn get(&self, x: i64, y: i64) -> bool {
+        if x < 0 || y < 0 {
+            return self.background;
+        }
+        *self
+            .pixels
+            .get(x as usize, y as usize)
+            .unwrap_or(&self.background)
+    }
+
+    fn index(&self, x: i64, y: i64) -> usize {
+        let mut index = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                index = index << 1 | self.get(x + dx, y + dy) as usize;
+            }
+        }
+        index
+    }
+
+    // the image grows by one pixel on every side, beyond that each pixel
+    // only sees background, so the background itself gets enhanced too.
+    fn enhance(&self, algorithm: &[bool]) -> Image {
+        let (width, height) = (self.pixels.width() + 2, self.pixels.height() + 2);
+        let mut pixels = Grid::new(width, height, false);
+        for (x, y) in pixels.positions().collect::<Vec<_>>() {
+            let index = self.index(x as i64 - 1, y as i64 - 1);
+            pixels.set(x, y, algorithm[index]);
+        }
+        let background = algorithm[if self.background {
+            ALGORITHM_LEN - 1
+        } else {
+            0
+        }];
+        Image { pixels, background }
+    }
+
+    // None when the infinite background is lit
+    fn lit(&self) -> Option<usize> {
+        if self.background {
+            return None;
+        }
+        Some(
+            self.pixels
+                .positions()
+                .filter(|&(x, y)| self.get(x as i64, y as i64))
+                .count(),
+        )
+    }
+
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(self.pixels.width(), self.pixels.height());
+        for (x, y) in self.pixels.positions() {
+            let cell = if self.get(x as i64, y as i64) {
+                Cell::new('#', visual::YELLOW)
+            } else {
+                Cell::new('.', visual::GREY)
+            };
+            frame.set(x, y, cell);
+        }
+        frame
+    }
+}
+
+fn pixel_row(line: &str) -> parse::Result<Vec<bool>> {
+    line.trim()
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(ParseError::new(i + 1, ErrorKind::InvalidDigit(c))),
+        })
+        .collect()
+}
+
+fn read_input<I, T>(lines: I) -> parse::Result<(Vec<bool>, Image)>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let sections = parse::sections(lines);
+    let (algorithm, image) = match &sections[..] {
+        [algorithm, image] => (algorithm, image),
+        [] => return Err(ParseError::new(1, ErrorKind::Empty)),
+        [algorithm] => {
+            let kind = ErrorKind::Unexpected {
+                found: "end of input".to_string(),
+                expected: "an image below the enhancement algorithm".to_string(),
+            };
+            let line = algorithm.first_line + algorithm.lines.len();
+            return Err(ParseError::new(1, kind).at_line(line));
+        }
+        [_, _, extra, ..] => {
+            let kind = ErrorKind::Unexpected {
+                found: extra.lines[0].clone(),
+                expected: "an enhancement algorithm followed by an image".to_string(),
+            };
+            return Err(ParseError::new(1, kind).at_line(extra.first_line));
+        }
+    };
+    // the algorithm may be wrapped over several lines
+    let algorithm_rows = algorithm.parse_lines(pixel_row)?;
+    let len = algorithm_rows.iter().map(|r| r.len()).sum::<usize>();
+    if len != ALGORITHM_LEN {
+        let kind = ErrorKind::Unexpected {
+            found: format!("{} entries", len),
+            expected: format!("an enhancement algorithm of {}", ALGORITHM_LEN),
+        };
+        return Err(ParseError::new(1, kind).at_line(algorithm.first_line));
+    }
+    let algorithm = algorithm_rows.into_iter().flatten().collect();
+
+    let rows = image.parse_lines(pixel_row)?;
+    for (i, row) in rows.iter().enumerate() {
+        if row.len() != rows[0].len() {
+            let kind = ErrorKind::RaggedRow {
+                expected: rows[0].len(),
+                found: row.len(),
+            };
+            return Err(ParseError::new(1, kind).at_line(image.first_line + i));
+        }
+    }
+    let image = Image {
+        pixels: Grid::from_rows(rows),
+        background: false,
+    };
+    Ok((algorithm, image))
+}
+
+fn solve_impl<I, T>(lines: I, steps: usize) -> parse::Result<Option<usize>>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let (algorithm, mut image) = read_input(lines)?;
+    for _ in 0..steps {
+        image = image.enhance(&algorithm);
+    }
+    Ok(image.lit())
+}
+
+fn default_steps(part: u8) -> usize {
+    match part {
+        1 => 2,
+        2 => 50,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "
+..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###
+";
+
+    #[test]
+    fn puzzle_example() {
+        assert_eq!(Ok(Some(35)), solve_impl(INPUT.lines(), 2));
+        assert_eq!(Ok(Some(3351)), solve_impl(INPUT.lines(), 50));
+    }
+
+    #[test]
+    fn bad_input() {
+        let algorithm = INPUT.trim().lines().next().unwrap();
+        let at = |input: &str| read_input(input.lines()).err().map(|e| (e.line, e.column));
+        assert_eq!(Some((1, 1)), at("#.#\n\n#.\n"));
+        assert_eq!(Some((2, 1)), at(algorithm));
+        let ragged = format!("{}\n\n#..\n#.\n", algorithm);
+        let err = read_input(ragged.lines()).err();
+        assert_eq!(
+            Some("line 4, column 1: row has 2 columns, expected 3".to_string()),
+            err.map(|e| e.to_string())
+        );
+    }
+
+    #[test]
+    fn flipping_background() {
+        // inverts the centre pixel, so a dark background lights up on odd
+        // steps and goes dark again on even ones
+        let algorithm: String = (0..ALGORITHM_LEN)
+            .map(|i| if i & 0b10000 == 0 { '#' } else { '.' })
+            .collect();
+        let input = format!("{}\n\n#..\n.#.\n", algorithm);
+        assert_eq!(Ok(Some(2)), solve_impl(input.lines(), 0));
+        assert_eq!(Ok(None), solve_impl(input.lines(), 1));
+        // every ring added so far is background, and flips with it
+        assert_eq!(Ok(Some(2)), solve_impl(input.lines(), 2));
+        assert_eq!(Ok(None), solve_impl(input.lines(), 3));
+    }
+}
+
+pub fn visualize<I: Iterator<Item = String>>(
+    lines: I,
+    part: u8,
+    params: &Params,
+    sink: &mut dyn FrameSink,
+) -> parse::Result<()> {
+    let steps = params.get_or("steps", default_steps(part))?;
+    let (algorithm, mut image) = read_input(lines)?;
+    for step in 0..=steps {
+        if step > 0 {
+            image = image.enhance(&algorithm);
+        }
+        let lit = image
+            .lit()
+            .map_or("infinite".to_string(), |n| n.to_string());
+        let caption = format!("step {}, lit: {}", step, lit);
+        sink.draw(&image.frame().with_caption(caption));
+    }
+    Ok(())
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8, params: &Params) -> parse::Result<()> {
+    let steps = params.get_or("steps", default_steps(part))?;
+    match solve_impl(lines, steps)? {
+        Some(lit) => println!("part{}: {}", part, lit),
+        None => println!("part{}: infinite, the background is lit", part),
+    }
+    Ok(())
+}
@@ -0,0 +1,318 @@
+use crate::params::Params;
+use crate::parse::{self, ErrorKind, ParseError};
+use std::collections::HashMap;
+
+// every turn a player rolls the die this many times and moves by the total
+const ROLLS_PER_TURN: u32 = 3;
+
+#[derive(Clone, Copy, Debug)]
+struct Rules {
+    board: u32,
+    target: u32,
+    sides: u32,
+}
+
+// positions and scores of both players, `turn` is the player to move next
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct State {
+    positions: [u32; 2],
+    scores: [u32; 2],
+    turn: usize,
+}
+
+impl State {
+    fn new(start: [u32; 2]) -> Self {
+        State {
+            positions: start,
+            scores: [0, 0],
+            turn: 0,
+        }
+    }
+
+    fn advance(&self, rules: &Rules, total: u32) -> State {
+        let mut next = *self;
+        let p = self.turn;
+        // positions are 1 based and wrap around the board
+        next.positions[p] = (self.positions[p] - 1 + total) % rules.board + 1;
+        next.scores[p] += next.positions[p];
+        next.turn = 1 - p;
+        next
+    }
+}
+
+// the starting positions, which have to be spaces 1 to `board`
+fn read_start<I, T>(lines: I, board: u32) -> parse::Result<[u32; 2]>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let position = |s: &str| {
+        let pos = parse::number::<u32>(s)?;
+        if !(1..=board).contains(&pos) {
+            let kind = ErrorKind::Unexpected {
+                found: pos.to_string(),
+                expected: format!("a position from 1 to {}", board),
+            };
+            return Err(ParseError::new(1 + s.len() - s.trim_start().len(), kind));
+        }
+        Ok(pos)
+    };
+    let lines: Vec<T> = lines.collect();
+    let start = parse::lines_with(lines.iter(), |l| {
+        parse::key_value(l, ':', position).map(|(_, pos)| pos)
+    })?;
+    if let [a, b] = start[..] {
+        return Ok([a, b]);
+    }
+    // either the line of a third player or just past the last line
+    let (line, found) = match lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !l.as_ref().trim().is_empty())
+        .nth(2)
+    {
+        Some((i, l)) => (i + 1, l.as_ref().trim().to_string()),
+        None => (lines.len() + 1, "end of input".to_string()),
+    };
+    let kind = ErrorKind::Unexpected {
+        found,
+        expected: "starting positions of two players".to_string(),
+    };
+    Err(ParseError::new(1, kind).at_line(line))
+}
+
+// losing score times the number of rolls, playing with a die that counts
+// 1, 2, .. sides and starts over.
+fn deterministic(start: [u32; 2], rules: &Rules) -> u64 {
+    let mut state = State::new(start);
+    let mut rolls: u64 = 0;
+    let mut die = (1..=rules.sides).cycle();
+    loop {
+        let total = (0..ROLLS_PER_TURN).map(|_| die.next().unwrap()).sum();
+        rolls += ROLLS_PER_TURN as u64;
+        let player = state.turn;
+        state = state.advance(rules, total);
+        if state.scores[player] >= rules.target {
+            return state.scores[1 - player] as u64 * rolls;
+        }
+    }
+}
+
+// how many of the sides^3 outcomes of a turn add up to each total
+fn roll_distribution(sides: u32) -> Vec<(u32, u128)> {
+    let mut totals = HashMap::from([(0, 1)]);
+    for _ in 0..ROLLS_PER_TURN {
+        let mut next = HashMap::new();
+        for (total, count) in totals {
+            for face in 1..=sides {
+                *next.entry(total + face).or_insert(0) += count;
+            }
+        }
+        totals = next;
+    }
+    let mut result: Vec<_> = totals.into_iter().collect();
+    result.sort_unstable();
+    result
+}
+
+struct Quantum<'a> {
+    rules: &'a Rules,
+    rolls: Vec<(u32, u128)>,
+    // universes won by each player from a given state
+    memo: HashMap<State, [u128; 2]>,
+}
+
+impl<'a> Quantum<'a> {
+    fn new(rules: &'a Rules) -> Self {
+        Quantum {
+            rules,
+            rolls: roll_distribution(rules.sides),
+            memo: HashMap::new(),
+        }
+    }
+
+    // None once a count no longer fits, the number of universes grows
+    // by sides^3 every turn and long games quickly exceed even u128.
+    fn wins(&mut self, state: State) -> Option<[u128; 2]> {
+        if let Some(&wins) = self.memo.get(&state) {
+            return Some(wins);
+        }
+        let mut wins = [0u128, 0];
+        let player = state.turn;
+        for i in 0..self.rolls.len() {
+            let (total, count) = self.rolls[i];
+            let next = state.advance(self.rules, total);
+            if next.scores[player] >= self.rules.target {
+                wins[player] = wins[player].checked_add(count)?;
+            } else {
+                let sub = self.wins(next)?;
+                for (w, s) in wins.iter_mut().zip(sub) {
+                    *w = w.checked_add(s.checked_mul(count)?)?;
+                }
+            }
+        }
+        self.memo.insert(state, wins);
+        Some(wins)
+    }
+}
+
+fn part1(start: [u32; 2], rules: &Rules) -> u64 {
+    deterministic(start, rules)
+}
+
+fn part2(start: [u32; 2], rules: &Rules) -> Option<u128> {
+    let wins = Quantum::new(rules).wins(State::new(start))?;
+    wins.iter().max().copied()
+}
+
+// the board and the dice need at least one space and side
+fn positive(s: &str) -> parse::Result<u32> {
+    match parse::number(s)? {
+        0 => Err(ParseError::new(
+            1,
+            ErrorKind::Unexpected {
+                found: s.to_string(),
+                expected: "a positive number".to_string(),
+            },
+        )),
+        n => Ok(n),
+    }
+}
+
+fn rules(part: u8, params: &Params) -> parse::Result<Rules> {
+    let (target, sides) = match part {
+        1 => (1000, 100),
+        2 => (21, 3),
+        _ => unreachable!(),
+    };
+    Ok(Rules {
+        board: params.get_with("board", positive)?.unwrap_or(10),
+        target: params.get_or("target", target)?,
+        sides: params.get_with("sides", positive)?.unwrap_or(sides),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "
+Player 1 starting position: 4
+Player 2 starting position: 8
+";
+
+    #[test]
+    fn puzzle_example() {
+        let start = read_start(INPUT.lines(), 10).unwrap();
+        assert_eq!([4, 8], start);
+        let params = Params::default();
+        assert_eq!(739785, part1(start, &rules(1, &params).unwrap()));
+        assert_eq!(
+            Some(444356092776315),
+            part2(start, &rules(2, &params).unwrap())
+        );
+    }
+
+    #[test]
+    fn start_off_the_board() {
+        // position 0 used to underflow on the first move
+        let err = read_start(["Player 1 starting position: 0"].iter(), 10).unwrap_err();
+        assert_eq!((1, 29), (err.line, err.column));
+        assert_eq!(
+            "line 1, column 29: unexpected '0', expected a position from 1 to 10",
+            err.to_string()
+        );
+        let err = read_start(INPUT.lines(), 7).unwrap_err();
+        assert_eq!(3, err.line);
+        assert!(read_start(INPUT.lines(), 8).is_ok());
+    }
+
+    #[test]
+    fn player_count() {
+        let err = read_start(["Player 1 starting position: 4"].iter(), 10).unwrap_err();
+        assert_eq!(
+            "line 2, column 1: unexpected 'end of input', expected starting positions of two players",
+            err.to_string()
+        );
+        let three = format!("{}Player 3 starting position: 1\n", INPUT);
+        let err = read_start(three.lines(), 10).unwrap_err();
+        assert_eq!((4, 1), (err.line, err.column));
+    }
+
+    #[test]
+    fn dirac_rolls() {
+        let rolls = roll_distribution(3);
+        assert_eq!(27, rolls.iter().map(|r| r.1).sum::<u128>());
+        assert_eq!(
+            vec![(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)],
+            rolls
+        );
+    }
+
+    // plays out every universe one by one
+    fn brute_force(state: State, rules: &Rules) -> [u128; 2] {
+        let mut wins = [0, 0];
+        let faces = rules.sides;
+        for roll in 0..faces.pow(ROLLS_PER_TURN) {
+            let total = (0..ROLLS_PER_TURN)
+                .map(|i| roll / faces.pow(i) % faces + 1)
+                .sum();
+            let next = state.advance(rules, total);
+            if next.scores[state.turn] >= rules.target {
+                wins[state.turn] += 1;
+            } else {
+                let sub = brute_force(next, rules);
+                wins[0] += sub[0];
+                wins[1] += sub[1];
+            }
+        }
+        wins
+    }
+
+    #[test]
+    fn game_variants() {
+        for (board, target, sides) in [(5, 3, 2), (7, 4, 2), (4, 2, 3)] {
+            let rules = Rules {
+                board,
+                target,
+                sides,
+            };
+            let state = State::new([2, board]);
+            assert_eq!(
+                Some(brute_force(state, &rules)),
+                Quantum::new(&rules).wins(state)
+            );
+        }
+    }
+
+    #[test]
+    fn too_many_universes() {
+        // on a single space board every turn scores 1, so the first player
+        // wins in all 27^(2 * target - 1) universes
+        let short = Rules {
+            board: 1,
+            target: 10,
+            sides: 3,
+        };
+        assert_eq!(Some(27u128.pow(19)), part2([1, 1], &short));
+        let long = Rules {
+            target: 40,
+            ..short
+        };
+        assert_eq!(None, part2([1, 1], &long));
+    }
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8, params: &Params) -> parse::Result<()> {
+    let rules = rules(part, params)?;
+    let start = read_start(lines, rules.board)?;
+    match part {
+        1 => println!("part1: {}", part1(start, &rules)),
+        2 => match part2(start, &rules) {
+            Some(wins) => println!("part2: {}", wins),
+            None => println!("part2: too many universes to count"),
+        },
+        _ => unreachable!(),
+    }
+    Ok(())
+}
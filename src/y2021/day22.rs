@@ -0,0 +1,204 @@
+use crate::parse::{self, ErrorKind, ParseError};
+use std::cmp;
+use std::collections::HashMap;
+
+/// An axis aligned box of cubes, bounds are inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Cuboid {
+    min: [i64; 3],
+    max: [i64; 3],
+}
+
+impl Cuboid {
+    fn intersect(&self, other: &Cuboid) -> Option<Cuboid> {
+        let mut result = *self;
+        for axis in 0..3 {
+            result.min[axis] = cmp::max(self.min[axis], other.min[axis]);
+            result.max[axis] = cmp::min(self.max[axis], other.max[axis]);
+            if result.min[axis] > result.max[axis] {
+                return None;
+            }
+        }
+        Some(result)
+    }
+
+    fn volume(&self) -> i64 {
+        (0..3)
+            .map(|axis| self.max[axis] - self.min[axis] + 1)
+            .product()
+    }
+}
+
+#[derive(Debug)]
+struct Step {
+    on: bool,
+    cuboid: Cuboid,
+}
+
+impl Step {
+    fn parse(line: &str) -> parse::Result<Self> {
+        let (on, ranges) = match parse::prefixed(line, "on ") {
+            Ok(rest) => (true, rest),
+            Err(_) => (
+                false,
+                parse::prefixed(line, "off ").map_err(|e| {
+                    let kind = ErrorKind::Unexpected {
+                        found: line.split_whitespace().next().unwrap_or("").to_string(),
+                        expected: "on or off".to_string(),
+                    };
+                    ParseError::new(e.column, kind)
+                })?,
+            ),
+        };
+        let offset = line.len() - ranges.len();
+
+        let range = |s| {
+            parse::key_value(s, '=', |r| {
+                parse::separated_pair(r, "..", parse::number::<i64>, parse::number::<i64>)
+            })
+            .map(|(_, (a, b))| (cmp::min(a, b), cmp::max(a, b)))
+        };
+        let (x, (y, z)) = parse::separated_pair(ranges, ",", range, |s| {
+            parse::separated_pair(s, ",", range, range)
+        })
+        .map_err(|e| ParseError::new(offset + e.column, e.kind))?;
+        Ok(Step {
+            on,
+            cuboid: Cuboid {
+                min: [x.0, y.0, z.0],
+                max: [x.1, y.1, z.1],
+            },
+        })
+    }
+}
+
+fn read_steps<I, T>(lines: I) -> parse::Result<Vec<Step>>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    parse::lines_with(lines, Step::parse)
+}
+
+// inclusion-exclusion over signed cuboids: switching a cuboid on or off
+// first cancels its overlap with everything counted so far, then an `on`
+// step adds the cuboid itself. Only the steps' parts inside `region` count.
+fn lit_cubes(steps: &[Step], region: Option<&Cuboid>) -> i64 {
+    let mut signed: HashMap<Cuboid, i64> = HashMap::new();
+    for step in steps {
+        let cuboid = match region {
+            Some(r) => match step.cuboid.intersect(r) {
+                Some(c) => c,
+                None => continue,
+            },
+            None => step.cuboid,
+        };
+
+        let mut update: HashMap<Cuboid, i64> = HashMap::new();
+        for (c, &sign) in &signed {
+            if let Some(overlap) = c.intersect(&cuboid) {
+                *update.entry(overlap).or_insert(0) -= sign;
+            }
+        }
+        if step.on {
+            *update.entry(cuboid).or_insert(0) += 1;
+        }
+        for (c, sign) in update {
+            *signed.entry(c).or_insert(0) += sign;
+        }
+        signed.retain(|_, sign| *sign != 0);
+    }
+    signed.iter().map(|(c, sign)| c.volume() * sign).sum()
+}
+
+const INIT_REGION: Cuboid = Cuboid {
+    min: [-50; 3],
+    max: [50; 3],
+};
+
+fn part1(steps: &[Step]) -> i64 {
+    lit_cubes(steps, Some(&INIT_REGION))
+}
+
+fn part2(steps: &[Step]) -> i64 {
+    lit_cubes(steps, None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Lcg;
+    use std::collections::HashSet;
+
+    #[test]
+    fn puzzle_example() {
+        let input = "
+on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10
+";
+        let steps = read_steps(input.lines()).unwrap();
+        assert_eq!(39, part1(&steps));
+        assert_eq!(39, part2(&steps));
+
+        let outside = read_steps("on x=-54..-49,y=0..1,z=49..60".lines()).unwrap();
+        assert_eq!(2 * 2 * 2, part1(&outside));
+        assert_eq!(6 * 2 * 12, part2(&outside));
+
+        assert_eq!(
+            "column 1: unexpected 'toggle', expected on or off",
+            Step::parse("toggle x=1..2,y=1..2,z=1..2")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(6, Step::parse("on x=1:2,y=1..2,z=1..2").unwrap_err().column);
+    }
+
+    // switches every cube on its own
+    fn brute_force(steps: &[Step]) -> usize {
+        let mut lit = HashSet::new();
+        for step in steps {
+            let Cuboid { min, max } = step.cuboid;
+            for x in min[0]..=max[0] {
+                for y in min[1]..=max[1] {
+                    for z in min[2]..=max[2] {
+                        if step.on {
+                            lit.insert((x, y, z));
+                        } else {
+                            lit.remove(&(x, y, z));
+                        }
+                    }
+                }
+            }
+        }
+        lit.len()
+    }
+
+    #[test]
+    fn overlapping_steps() {
+        let mut rng = Lcg::new(22);
+        let mut rand = |n: i64| rng.below(n as u64) as i64;
+        let steps: Vec<Step> = (0..40)
+            .map(|_| {
+                let min = [rand(12) - 6, rand(12) - 6, rand(12) - 6];
+                let max = [min[0] + rand(8), min[1] + rand(8), min[2] + rand(8)];
+                Step {
+                    on: rand(3) > 0,
+                    cuboid: Cuboid { min, max },
+                }
+            })
+            .collect();
+        assert_eq!(brute_force(&steps) as i64, part2(&steps));
+    }
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let steps = read_steps(lines)?;
+    match part {
+        1 => println!("part1: {}", part1(&steps)),
+        2 => println!("part2: {}", part2(&steps)),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
@@ -0,0 +1,394 @@
+use crate::graph;
+use crate::params::Params;
+use crate::parse::{self, ErrorKind, ParseError};
+use log::debug;
+use std::fmt;
+
+const HALLWAY: usize = 11;
+const ROOMS: usize = 4;
+const MAX_DEPTH: usize = 4;
+const EMPTY: u8 = b'.';
+
+// the two rows folded out of the diagram in part 2
+const UNFOLDED: [[u8; ROOMS]; 2] = [*b"DCBA", *b"DBAC"];
+
+fn owner(room: usize) -> u8 {
+    b'A' + room as u8
+}
+
+fn energy(kind: u8) -> u64 {
+    10u64.pow((kind - b'A') as u32)
+}
+
+// hallway position just outside a room, amphipods never stop there
+fn entrance(room: usize) -> usize {
+    2 + 2 * room
+}
+
+fn is_entrance(pos: usize) -> bool {
+    (0..ROOMS).any(|r| entrance(r) == pos)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Spot {
+    Hallway(usize),
+    Room(usize, usize),
+}
+
+impl fmt::Display for Spot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Spot::Hallway(pos) => write!(f, "hallway {}", pos),
+            Spot::Room(room, slot) => write!(f, "room {} slot {}", owner(*room) as char, slot),
+        }
+    }
+}
+
+// rooms are filled top down, slot 0 being next to the hallway. Only the
+// first `depth` slots of each room are in use.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Burrow {
+    hallway: [u8; HALLWAY],
+    rooms: [[u8; MAX_DEPTH]; ROOMS],
+    depth: usize,
+}
+
+impl Burrow {
+    fn parse<I, T>(lines: I) -> parse::Result<Self>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let cell = |line: &[u8], col: usize| match line.get(col) {
+            Some(&c) if c == EMPTY || (b'A'..=b'D').contains(&c) => Ok(c),
+            Some(&c) => Err(ParseError::new(col + 1, ErrorKind::InvalidDigit(c as char))),
+            None => Err(ParseError::new(
+                col + 1,
+                ErrorKind::RaggedRow {
+                    expected: col + 1,
+                    found: line.len(),
+                },
+            )),
+        };
+
+        let mut burrow = Burrow {
+            hallway: [EMPTY; HALLWAY],
+            rooms: [[EMPTY; MAX_DEPTH]; ROOMS],
+            depth: 0,
+        };
+        // line numbers of the hallway and of every row of rooms
+        let mut rows = vec![];
+        for (i, line) in lines.enumerate() {
+            let text = line.as_ref();
+            let line = text.as_bytes();
+            let at_line = |e: ParseError| e.at_line(i + 1);
+            // the walls around the diagram carry no information
+            if !line.iter().any(|&c| c != b'#' && c != b' ') {
+                continue;
+            }
+            if rows.is_empty() {
+                for pos in 0..HALLWAY {
+                    burrow.hallway[pos] = cell(line, pos + 1).map_err(at_line)?;
+                }
+                rows.push(i + 1);
+                continue;
+            }
+            // rooms deeper than the unfolded burrow should be walled off
+            if burrow.depth == MAX_DEPTH {
+                let kind = ErrorKind::Unexpected {
+                    found: text.trim().to_string(),
+                    expected: "the bottom wall".to_string(),
+                };
+                return Err(at_line(ParseError::new(1, kind)));
+            }
+            for room in 0..ROOMS {
+                burrow.rooms[room][burrow.depth] =
+                    cell(line, entrance(room) + 1).map_err(at_line)?;
+            }
+            burrow.depth += 1;
+            rows.push(i + 1);
+        }
+        if burrow.depth == 0 {
+            let kind = ErrorKind::Unexpected {
+                found: "end of input".to_string(),
+                expected: "a hallway above a row of rooms".to_string(),
+            };
+            return Err(ParseError::new(1, kind).at_line(rows.len() + 1));
+        }
+        burrow.check_counts(&rows)?;
+        Ok(burrow)
+    }
+
+    // every kind has to fill exactly the slots of its room, more could
+    // never all fit in and with fewer the room is never finished.
+    fn check_counts(&self, rows: &[usize]) -> parse::Result<()> {
+        let mut counts = [0; ROOMS];
+        for spot in self.spots() {
+            let kind = self.get(spot);
+            if kind == EMPTY {
+                continue;
+            }
+            let n = &mut counts[(kind - b'A') as usize];
+            *n += 1;
+            if *n > self.depth {
+                let (line, col) = match spot {
+                    Spot::Hallway(pos) => (rows[0], pos + 2),
+                    Spot::Room(room, slot) => (rows[slot + 1], entrance(room) + 2),
+                };
+                let kind = ErrorKind::Unexpected {
+                    found: format!("amphipod {} number {}", kind as char, n),
+                    expected: format!("{} of each kind, one per room slot", self.depth),
+                };
+                return Err(ParseError::new(col, kind).at_line(line));
+            }
+        }
+        if let Some(room) = (0..ROOMS).find(|&r| counts[r] < self.depth) {
+            let kind = ErrorKind::Unexpected {
+                found: format!("{} of amphipod {}", counts[room], owner(room) as char),
+                expected: format!("{} of each kind, one per room slot", self.depth),
+            };
+            return Err(ParseError::new(1, kind).at_line(rows[rows.len() - 1]));
+        }
+        Ok(())
+    }
+
+    // inserts the two folded rows between the first and the last row, any
+    // burrow that is not 2 deep has nothing folded and is left as it is.
+    fn unfold(&self) -> Burrow {
+        if self.depth != 2 {
+            return self.clone();
+        }
+        let mut result = self.clone();
+        for (room, slots) in result.rooms.iter_mut().enumerate() {
+            *slots = [slots[0], UNFOLDED[0][room], UNFOLDED[1][room], slots[1]];
+        }
+        result.depth = MAX_DEPTH;
+        result
+    }
+
+    fn room(&self, room: usize) -> &[u8] {
+        &self.rooms[room][..self.depth]
+    }
+
+    fn get(&self, spot: Spot) -> u8 {
+        match spot {
+            Spot::Hallway(pos) => self.hallway[pos],
+            Spot::Room(room, slot) => self.rooms[room][slot],
+        }
+    }
+
+    fn set(&mut self, spot: Spot, val: u8) {
+        match spot {
+            Spot::Hallway(pos) => self.hallway[pos] = val,
+            Spot::Room(room, slot) => self.rooms[room][slot] = val,
+        }
+    }
+
+    // a room can be entered once it only holds amphipods that belong there
+    fn accepts(&self, room: usize) -> bool {
+        self.room(room)
+            .iter()
+            .all(|&c| c == EMPTY || c == owner(room))
+    }
+
+    // the hallway between `from` and `to` is free, `from` itself excluded
+    fn clear(&self, from: usize, to: usize) -> bool {
+        let path = if from < to {
+            from + 1..=to
+        } else {
+            to..=from - 1
+        };
+        self.hallway[path].iter().all(|&c| c == EMPTY)
+    }
+
+    fn step(&self, from: Spot, to: Spot, distance: usize) -> (Burrow, u64) {
+        let kind = self.get(from);
+        let mut next = self.clone();
+        next.set(from, EMPTY);
+        next.set(to, kind);
+        (next, distance as u64 * energy(kind))
+    }
+
+    fn moves(&self) -> Vec<(Burrow, u64)> {
+        // walking straight home is never worse than anything else, so when
+        // some amphipod can, that is the only move worth considering.
+        for pos in 0..HALLWAY {
+            let kind = self.hallway[pos];
+            if kind == EMPTY {
+                continue;
+            }
+            let room = (kind - b'A') as usize;
+            if !self.accepts(room) || !self.clear(pos, entrance(room)) {
+                continue;
+            }
+            let slot = self.room(room).iter().rposition(|&c| c == EMPTY).unwrap();
+            let distance = pos.abs_diff(entrance(room)) + slot + 1;
+            return vec![self.step(Spot::Hallway(pos), Spot::Room(room, slot), distance)];
+        }
+
+        let mut result = vec![];
+        for room in 0..ROOMS {
+            if self.accepts(room) {
+                continue;
+            }
+            let slot = match self.room(room).iter().position(|&c| c != EMPTY) {
+                Some(slot) => slot,
+                None => continue,
+            };
+            for pos in (0..HALLWAY).filter(|&p| !is_entrance(p)) {
+                if self.clear(entrance(room), pos) {
+                    let distance = pos.abs_diff(entrance(room)) + slot + 1;
+                    result.push(self.step(Spot::Room(room, slot), Spot::Hallway(pos), distance));
+                }
+            }
+        }
+        result
+    }
+
+    fn is_organised(&self) -> bool {
+        (0..ROOMS).all(|r| self.room(r).iter().all(|&c| c == owner(r)))
+    }
+
+    fn spots(&self) -> impl Iterator<Item = Spot> + '_ {
+        let hallway = (0..HALLWAY).map(Spot::Hallway);
+        let rooms = (0..ROOMS).flat_map(move |r| (0..self.depth).map(move |s| Spot::Room(r, s)));
+        hallway.chain(rooms)
+    }
+
+    // which amphipod moved where between two consecutive burrow states
+    fn describe(&self, next: &Burrow) -> String {
+        let from = self
+            .spots()
+            .find(|&s| self.get(s) != EMPTY && next.get(s) == EMPTY);
+        let to = self
+            .spots()
+            .find(|&s| self.get(s) == EMPTY && next.get(s) != EMPTY);
+        match (from, to) {
+            (Some(from), Some(to)) => {
+                format!("{} from {} to {}", next.get(to) as char, from, to)
+            }
+            _ => "no move".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "#############")?;
+        writeln!(f, "#{}#", String::from_utf8_lossy(&self.hallway))?;
+        for slot in 0..self.depth {
+            let cells: Vec<String> = (0..ROOMS)
+                .map(|r| (self.rooms[r][slot] as char).to_string())
+                .collect();
+            if slot == 0 {
+                writeln!(f, "###{}###", cells.join("#"))?;
+            } else {
+                writeln!(f, "  #{}#", cells.join("#"))?;
+            }
+        }
+        write!(f, "  #########")
+    }
+}
+
+// least energy to organise the amphipods, with every state along the way
+fn organise(burrow: Burrow) -> Option<(u64, Vec<Burrow>)> {
+    graph::dijkstra(burrow, |b| b.moves(), |b| b.is_organised())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "
+#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+";
+
+    #[test]
+    fn puzzle_example() {
+        let burrow = Burrow::parse(INPUT.lines()).unwrap();
+        assert_eq!(INPUT.trim(), burrow.to_string());
+        let (energy, path) = organise(burrow.clone()).unwrap();
+        assert_eq!(12521, energy);
+        assert!(path.last().unwrap().is_organised());
+
+        let unfolded = burrow.unfold();
+        assert_eq!("  #D#C#B#A#", unfolded.to_string().lines().nth(3).unwrap());
+        assert_eq!(44169, organise(unfolded).unwrap().0);
+    }
+
+    #[test]
+    fn single_moves() {
+        let input = "
+#############
+#.........A.#
+###.#B#C#D###
+  #A#B#C#D#
+  #########
+";
+        let burrow = Burrow::parse(input.lines()).unwrap();
+        let (energy, path) = organise(burrow).unwrap();
+        assert_eq!(8, energy);
+        assert_eq!(
+            "A from hallway 9 to room A slot 0",
+            path[0].describe(&path[1])
+        );
+
+        let bad = "#############\n#...........#\n###B#C#X#D###\n";
+        assert_eq!(8, Burrow::parse(bad.lines()).unwrap_err().column);
+    }
+
+    #[test]
+    fn amphipod_counts() {
+        let at = |input: &str| {
+            Burrow::parse(input.lines())
+                .err()
+                .map(|e| (e.line, e.column))
+        };
+        // a third A has no slot left in its room
+        assert_eq!(
+            Some((3, 10)),
+            at("#A..........#\n###B#C#B#D###\n  #A#D#C#A#\n")
+        );
+        // with a D missing room D can never be finished
+        assert_eq!(
+            Some((3, 1)),
+            at("#...........#\n###B#C#B#.###\n  #A#D#C#A#\n")
+        );
+        assert_eq!(Some((2, 1)), at("#...........#\n"));
+
+        let burrow = Burrow::parse(INPUT.lines()).unwrap();
+        let unfolded = burrow.unfold();
+        assert_eq!(unfolded, unfolded.unfold());
+    }
+}
+
+// `-P moves=true` prints every move of the cheapest sequence
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8, params: &Params) -> parse::Result<()> {
+    let show_moves = params.get_or("moves", false)?;
+    let burrow = Burrow::parse(lines)?;
+    let burrow = match part {
+        1 => burrow,
+        2 => burrow.unfold(),
+        _ => unreachable!(),
+    };
+    let (energy, path) = match organise(burrow) {
+        Some(found) => found,
+        None => {
+            println!("part{}: the amphipods cannot be organised", part);
+            return Ok(());
+        }
+    };
+    debug!("start:\n{}", path[0]);
+    for w in path.windows(2) {
+        if show_moves {
+            println!("{}", w[0].describe(&w[1]));
+        }
+        debug!("\n{}", w[1]);
+    }
+    println!("part{}: {}", part, energy);
+    Ok(())
+}
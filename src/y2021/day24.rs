@@ -0,0 +1,405 @@
+use crate::parse::{self, ErrorKind, ParseError};
+
+type Reg = usize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operand {
+    Reg(Reg),
+    Value(i64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Instr {
+    Inp(Reg),
+    Add(Reg, Operand),
+    Mul(Reg, Operand),
+    Div(Reg, Operand),
+    Mod(Reg, Operand),
+    Eql(Reg, Operand),
+}
+
+fn register(s: &str) -> parse::Result<Reg> {
+    match parse::ident(s)? {
+        "w" => Ok(0),
+        "x" => Ok(1),
+        "y" => Ok(2),
+        "z" => Ok(3),
+        other => Err(ParseError::new(
+            1,
+            ErrorKind::Unexpected {
+                found: other.to_string(),
+                expected: "a register w, x, y or z".to_string(),
+            },
+        )),
+    }
+}
+
+fn operand(s: &str) -> parse::Result<Operand> {
+    register(s)
+        .map(Operand::Reg)
+        .or_else(|_| parse::number(s.trim()).map(Operand::Value))
+}
+
+// whitespace separated words along with the column each starts at
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut result = vec![];
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                result.push((s + 1, &line[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+impl Instr {
+    fn parse(line: &str) -> parse::Result<Self> {
+        let fields = words(line);
+        let (&(op_col, op), args) = fields
+            .split_first()
+            .ok_or(ParseError::new(1, ErrorKind::Empty))?;
+        let arity = match op {
+            "inp" => 1,
+            "add" | "mul" | "div" | "mod" | "eql" => 2,
+            _ => {
+                let kind = ErrorKind::Unexpected {
+                    found: op.to_string(),
+                    expected: "one of inp, add, mul, div, mod or eql".to_string(),
+                };
+                return Err(ParseError::new(op_col, kind));
+            }
+        };
+        if let Some(&(col, extra)) = args.get(arity) {
+            let kind = ErrorKind::Unexpected {
+                found: extra.to_string(),
+                expected: "end of line".to_string(),
+            };
+            return Err(ParseError::new(col, kind));
+        }
+        let reg = Self::arg(args.first(), line, register)?;
+        if op == "inp" {
+            return Ok(Instr::Inp(reg));
+        }
+        let val = Self::arg(args.get(1), line, operand)?;
+        Ok(match op {
+            "add" => Instr::Add(reg, val),
+            "mul" => Instr::Mul(reg, val),
+            "div" => Instr::Div(reg, val),
+            "mod" => Instr::Mod(reg, val),
+            _ => Instr::Eql(reg, val),
+        })
+    }
+
+    // parses one of the words after the op, placing errors in `line`
+    fn arg<V>(
+        word: Option<&(usize, &str)>,
+        line: &str,
+        parse: fn(&str) -> parse::Result<V>,
+    ) -> parse::Result<V> {
+        let &(col, s) = word.ok_or_else(|| ParseError::new(line.len() + 1, ErrorKind::Empty))?;
+        parse(s).map_err(|e| ParseError::new(col + e.column - 1, e.kind))
+    }
+}
+
+fn read_program<I, T>(lines: I) -> parse::Result<Vec<Instr>>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    parse::lines_with(lines, Instr::parse)
+}
+
+// runs `program` on `input` and returns the registers w, x, y and z. None
+// when the ALU crashes, i.e. divides by zero, takes the remainder of a
+// negative number or by a non-positive one, overflows or runs out of input.
+fn run(program: &[Instr], input: &[i64]) -> Option<[i64; 4]> {
+    let mut regs = [0i64; 4];
+    let mut input = input.iter();
+    for instr in program {
+        let value = |regs: &[i64; 4], op: Operand| match op {
+            Operand::Reg(r) => regs[r],
+            Operand::Value(v) => v,
+        };
+        match *instr {
+            Instr::Inp(a) => regs[a] = *input.next()?,
+            Instr::Add(a, b) => regs[a] = regs[a].checked_add(value(&regs, b))?,
+            Instr::Mul(a, b) => regs[a] = regs[a].checked_mul(value(&regs, b))?,
+            Instr::Div(a, b) => regs[a] = regs[a].checked_div(value(&regs, b))?,
+            Instr::Mod(a, b) => {
+                let b = value(&regs, b);
+                if regs[a] < 0 || b <= 0 {
+                    return None;
+                }
+                regs[a] %= b;
+            }
+            Instr::Eql(a, b) => regs[a] = (regs[a] == value(&regs, b)) as i64,
+        }
+    }
+    Some(regs)
+}
+
+// every digit of MONAD is checked by the same 18 instructions, differing in
+// the three parameters: div z {div}, add x {check} and add y {offset}.
+const BLOCK: &str = "
+inp w
+mul x 0
+add x z
+mod x 26
+div z 0
+add x 0
+eql x w
+eql x 0
+mul y 0
+add y 25
+mul y x
+add y 1
+mul z y
+mul y 0
+add y w
+add y 0
+mul y x
+add z y
+";
+const DIV: usize = 4;
+const CHECK: usize = 5;
+const OFFSET: usize = 15;
+
+// z works as a stack of base 26 numbers. A block with `div z 1` pushes
+// digit + offset. One with `div z 26` pops the top and, unless the digit
+// equals top + check, pushes again. MONAD accepts when z ends up empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Block {
+    pops: bool,
+    check: i64,
+    offset: i64,
+}
+
+fn analyse(program: &[Instr]) -> Option<Vec<Block>> {
+    let template = read_program(BLOCK.lines()).expect("the block template parses");
+    if !program.len().is_multiple_of(template.len()) {
+        return None;
+    }
+    let param = |instr: &Instr| match *instr {
+        Instr::Div(_, Operand::Value(v)) | Instr::Add(_, Operand::Value(v)) => Some(v),
+        _ => None,
+    };
+    // the parameter slots with their value zeroed, as in the template,
+    // which keeps the op and register to compare.
+    let shape = |instr: &Instr| match *instr {
+        Instr::Div(r, Operand::Value(_)) => Some(Instr::Div(r, Operand::Value(0))),
+        Instr::Add(r, Operand::Value(_)) => Some(Instr::Add(r, Operand::Value(0))),
+        _ => None,
+    };
+
+    let mut blocks = vec![];
+    for chunk in program.chunks(template.len()) {
+        for (i, (instr, expected)) in chunk.iter().zip(&template).enumerate() {
+            let same = if [DIV, CHECK, OFFSET].contains(&i) {
+                shape(instr) == Some(*expected)
+            } else {
+                instr == expected
+            };
+            if !same {
+                return None;
+            }
+        }
+        let div = param(&chunk[DIV])?;
+        if div != 1 && div != 26 {
+            return None;
+        }
+        blocks.push(Block {
+            pops: div == 26,
+            check: param(&chunk[CHECK])?,
+            offset: param(&chunk[OFFSET])?,
+        });
+    }
+    Some(blocks)
+}
+
+// every popping block pairs up with the push before it, constraining its
+// digit to the pushed digit + offset + check. Returns the largest and the
+// smallest digits satisfying every pair, None if some pair can't be met.
+fn model_numbers(blocks: &[Block]) -> Option<(Vec<i64>, Vec<i64>)> {
+    let mut largest = vec![0; blocks.len()];
+    let mut smallest = vec![0; blocks.len()];
+    let mut stack = vec![];
+    for (i, block) in blocks.iter().enumerate() {
+        if !block.pops {
+            // a check above 9 never matches a digit so the block always
+            // pushes, anything else is beyond this analysis
+            if block.check <= 9 {
+                return None;
+            }
+            stack.push(i);
+            continue;
+        }
+        let j = stack.pop()?;
+        let delta = blocks[j].offset + block.check;
+        if delta.abs() > 8 {
+            return None;
+        }
+        largest[j] = 9 - delta.max(0);
+        largest[i] = largest[j] + delta;
+        smallest[j] = 1 - delta.min(0);
+        smallest[i] = smallest[j] + delta;
+    }
+    if !stack.is_empty() {
+        return None;
+    }
+    Some((largest, smallest))
+}
+
+fn accepts(program: &[Instr], digits: &[i64]) -> bool {
+    run(program, digits).is_some_and(|regs| regs[3] == 0)
+}
+
+fn to_number(digits: &[i64]) -> u64 {
+    digits.iter().fold(0, |acc, &d| acc * 10 + d as u64)
+}
+
+fn solve_impl(program: &[Instr]) -> Result<(u64, u64), &'static str> {
+    let blocks = analyse(program).ok_or("the program does not look like MONAD")?;
+    let (largest, smallest) = model_numbers(&blocks).ok_or("MONAD accepts no model number")?;
+    // the analysis relies on the shape of the program, double check it
+    if !accepts(program, &largest) || !accepts(program, &smallest) {
+        return Err("MONAD rejects the model numbers found");
+    }
+    Ok((to_number(&largest), to_number(&smallest)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interpreter() {
+        let negate = read_program("inp x\nmul x -1".lines()).unwrap();
+        assert_eq!(Some(-7), run(&negate, &[7]).map(|r| r[1]));
+
+        let triple = read_program("inp z\ninp x\nmul z 3\neql z x".lines()).unwrap();
+        assert_eq!(Some(1), run(&triple, &[2, 6]).map(|r| r[3]));
+        assert_eq!(Some(0), run(&triple, &[2, 7]).map(|r| r[3]));
+        assert_eq!(None, run(&triple, &[2]));
+
+        let bits = "
+inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2
+";
+        let bits = read_program(bits.lines()).unwrap();
+        assert_eq!(Some([1, 1, 0, 1]), run(&bits, &[13]));
+        assert_eq!(None, run(&read_program("div x 0".lines()).unwrap(), &[]));
+        // overflowing used to panic
+        let square = read_program("inp x\nmul x x\nmul x x".lines()).unwrap();
+        assert_eq!(Some(1 << 40), run(&square, &[1 << 10]).map(|r| r[1]));
+        assert_eq!(None, run(&square, &[1 << 32]));
+        let sum = read_program("inp x\nadd x x".lines()).unwrap();
+        assert_eq!(None, run(&sum, &[i64::MAX]));
+    }
+
+    #[test]
+    fn bad_instructions() {
+        let err = |line: &str| Instr::parse(line).unwrap_err().to_string();
+        assert_eq!(
+            "column 2: unexpected 'sub', expected one of inp, add, mul, div, mod or eql",
+            err(" sub x 1")
+        );
+        assert_eq!(
+            "column 1: unexpected 'nop', expected one of inp, add, mul, div, mod or eql",
+            err("nop")
+        );
+        assert_eq!(
+            "column 5: unexpected 'v', expected a register w, x, y or z",
+            err("add v 1")
+        );
+        assert_eq!(
+            "column 9: unexpected '2', expected end of line",
+            err("add x 1 2")
+        );
+        assert_eq!(
+            "column 8: unexpected 'y', expected end of line",
+            err("inp x  y")
+        );
+        assert_eq!(ErrorKind::Empty, Instr::parse("mul x").unwrap_err().kind);
+    }
+
+    fn monad(blocks: &[(i64, i64, i64)]) -> Vec<Instr> {
+        let mut text = String::new();
+        for &(div, check, offset) in blocks {
+            for (i, line) in BLOCK.trim().lines().enumerate() {
+                let line = match i {
+                    DIV => format!("div z {}", div),
+                    CHECK => format!("add x {}", check),
+                    OFFSET => format!("add y {}", offset),
+                    _ => line.to_string(),
+                };
+                text.push_str(&line);
+                text.push('\n');
+            }
+        }
+        read_program(text.lines()).unwrap()
+    }
+
+    #[test]
+    fn digit_constraints() {
+        // digit 2 = digit 1 - 2 and digit 3 = digit 0 + 3
+        let program = monad(&[(1, 12, 4), (1, 11, 7), (26, -9, 0), (26, -1, 5)]);
+        assert_eq!(Ok((6979, 1314)), solve_impl(&program));
+
+        let mut accepted = vec![];
+        for n in 0..9i64.pow(4) {
+            let digits: Vec<i64> = (0..4).rev().map(|i| n / 9i64.pow(i) % 9 + 1).collect();
+            if accepts(&program, &digits) {
+                accepted.push(to_number(&digits));
+            }
+        }
+        assert_eq!(Some(&6979), accepted.iter().max());
+        assert_eq!(Some(&1314), accepted.iter().min());
+
+        // pushed digit + 14 - 4 can never be a digit
+        let blocks = analyse(&monad(&[(1, 13, 14), (26, -4, 0)])).unwrap();
+        assert_eq!(None, model_numbers(&blocks));
+        assert_eq!(
+            None,
+            analyse(&read_program("inp w\nadd z w".lines()).unwrap())
+        );
+
+        // the same parameters added to the wrong registers
+        let mut swapped = monad(&[(1, 12, 4), (26, -4, 0)]);
+        swapped[CHECK] = Instr::Add(2, Operand::Value(12));
+        assert_eq!(None, analyse(&swapped));
+        let mut swapped = monad(&[(1, 12, 4), (26, -4, 0)]);
+        swapped[DIV] = Instr::Div(1, Operand::Value(1));
+        assert_eq!(None, analyse(&swapped));
+    }
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let program = read_program(lines)?;
+    let (largest, smallest) = match solve_impl(&program) {
+        Ok(found) => found,
+        Err(reason) => {
+            println!("part{}: {}", part, reason);
+            return Ok(());
+        }
+    };
+    match part {
+        1 => println!("part1: {}", largest),
+        2 => println!("part2: {}", smallest),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
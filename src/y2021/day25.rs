@@ -0,0 +1,189 @@
+use crate::grid::Grid;
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::visual::{self, Cell, Frame, FrameSink};
+use std::cell;
+
+const EMPTY: u8 = b'.';
+const EAST: u8 = b'>';
+const SOUTH: u8 = b'v';
+
+// the sea floor wraps around on both axes
+struct SeaFloor {
+    grid: Grid<u8>,
+}
+
+impl SeaFloor {
+    fn from_lines<I, T>(lines: I) -> parse::Result<Self>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        // every row has to be as wide as the first one
+        let width = cell::Cell::new(None);
+        let rows = parse::lines_with(lines, |l| {
+            let row = l
+                .trim()
+                .bytes()
+                .enumerate()
+                .map(|(i, c)| match c {
+                    EMPTY | EAST | SOUTH => Ok(c),
+                    _ => Err(ParseError::new(i + 1, ErrorKind::InvalidDigit(c as char))),
+                })
+                .collect::<parse::Result<Vec<u8>>>()?;
+            let expected = width.get().unwrap_or(row.len());
+            if row.len() != expected {
+                let kind = ErrorKind::RaggedRow {
+                    expected,
+                    found: row.len(),
+                };
+                return Err(ParseError::new(1, kind));
+            }
+            width.set(Some(expected));
+            Ok(row)
+        })?;
+        Ok(SeaFloor {
+            grid: Grid::from_rows(rows),
+        })
+    }
+
+    // moves every cucumber of the herd that faces a free spot at once,
+    // returning how many moved.
+    fn move_herd(&mut self, herd: u8) -> usize {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let ahead = |x: usize, y: usize| match herd {
+            EAST => ((x + 1) % width, y),
+            _ => (x, (y + 1) % height),
+        };
+        let moving: Vec<_> = self
+            .grid
+            .positions()
+            .filter(|&(x, y)| {
+                let (nx, ny) = ahead(x, y);
+                self.grid.get(x, y) == Some(&herd) && self.grid.get(nx, ny) == Some(&EMPTY)
+            })
+            .collect();
+        for &(x, y) in &moving {
+            let (nx, ny) = ahead(x, y);
+            self.grid.set(x, y, EMPTY);
+            self.grid.set(nx, ny, herd);
+        }
+        moving.len()
+    }
+
+    fn step(&mut self) -> usize {
+        self.move_herd(EAST) + self.move_herd(SOUTH)
+    }
+
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(self.grid.width(), self.grid.height());
+        for (x, y) in self.grid.positions() {
+            let cell = match *self.grid.get(x, y).unwrap() {
+                EAST => Cell::new('>', visual::YELLOW),
+                SOUTH => Cell::new('v', visual::WHITE),
+                _ => Cell::new('.', visual::GREY),
+            };
+            frame.set(x, y, cell);
+        }
+        frame
+    }
+}
+
+fn part1(floor: &mut SeaFloor) -> usize {
+    let mut steps = 1;
+    while floor.step() > 0 {
+        steps += 1;
+    }
+    steps
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rows(floor: &SeaFloor) -> Vec<String> {
+        (0..floor.grid.height())
+            .map(|y| {
+                (0..floor.grid.width())
+                    .map(|x| *floor.grid.get(x, y).unwrap() as char)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn herd_order() {
+        let mut row = SeaFloor::from_lines("...>>>>>...".lines()).unwrap();
+        row.step();
+        assert_eq!(vec!["...>>>>.>.."], rows(&row));
+        row.step();
+        assert_eq!(vec!["...>>>.>.>."], rows(&row));
+
+        // the east herd moves first, then wrapping south movers
+        let input = "
+..........
+.>v....v..
+.......>..
+..........
+";
+        let mut floor = SeaFloor::from_lines(input.lines()).unwrap();
+        floor.step();
+        assert_eq!(
+            vec!["..........", ".>........", "..v....v>.", ".........."],
+            rows(&floor)
+        );
+    }
+
+    #[test]
+    fn puzzle_example() {
+        let input = "
+v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>
+";
+        let mut floor = SeaFloor::from_lines(input.lines()).unwrap();
+        assert_eq!(58, part1(&mut floor));
+
+        let err = SeaFloor::from_lines("v..\n\n>.\n".lines()).err();
+        assert_eq!(
+            Some("line 3, column 1: row has 2 columns, expected 3".to_string()),
+            err.map(|e| e.to_string())
+        );
+    }
+}
+
+pub fn visualize<I: Iterator<Item = String>>(
+    lines: I,
+    _part: u8,
+    sink: &mut dyn FrameSink,
+) -> parse::Result<()> {
+    let mut floor = SeaFloor::from_lines(lines)?;
+    sink.draw(&floor.frame().with_caption("step 0".to_string()));
+    let mut step = 0;
+    loop {
+        step += 1;
+        let moved = floor.step();
+        let caption = format!("step {}, moved: {}", step, moved);
+        sink.draw(&floor.frame().with_caption(caption));
+        if moved == 0 {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub fn solve<I: Iterator<Item = String>>(lines: I, part: u8) -> parse::Result<()> {
+    let mut floor = SeaFloor::from_lines(lines)?;
+    match part {
+        1 => println!("part1: {}", part1(&mut floor)),
+        // the last star comes for free with the other 49
+        2 => println!("part2: none, day 25 has a single puzzle"),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
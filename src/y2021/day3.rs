@@ -0,0 +1,468 @@
+use crate::bits::Bits;
+use crate::params::Params;
+use crate::parse::{self, ErrorKind, ParseError};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::str::FromStr;
+
+// a reading as wide as `width`, or as it comes when that is not known yet
+fn read_bits(line: &str, width: Option<usize>) -> parse::Result<Bits> {
+    let col = line.len() - line.trim_start().len();
+    let bits = Bits::parse(line.trim()).map_err(|e| ParseError::new(col + e.column, e.kind))?;
+    match width {
+        Some(expected) if expected != bits.width() => {
+            let kind = ErrorKind::RaggedRow {
+                expected,
+                found: bits.width(),
+            };
+            Err(ParseError::new(1, kind))
+        }
+        _ => Ok(bits),
+    }
+}
+
+mod part1 {
+    use super::read_bits;
+    use crate::bits::Bits;
+    use crate::parse::{self, ErrorKind, ParseError};
+    use std::fmt;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Power {
+        pub gamma: Bits,
+        pub epsilon: Bits,
+    }
+
+    impl Power {
+        pub fn consumption(&self) -> Bits {
+            self.gamma.mul(&self.epsilon)
+        }
+    }
+
+    pub struct PowerMeter<I, T>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        // ones minus zeros in every column, sized by the first reading
+        ones_count: Vec<i64>,
+        line: usize,
+        input: I,
+    }
+
+    impl<I, T> PowerMeter<I, T>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        pub fn new(input: I) -> Self {
+            PowerMeter {
+                ones_count: vec![],
+                line: 0,
+                input,
+            }
+        }
+
+        fn process_next_input(&mut self, line: &str) -> parse::Result<()> {
+            let width = Some(self.ones_count.len()).filter(|&w| w > 0);
+            let reading = read_bits(line, width)?;
+            if width.is_none() {
+                self.ones_count = vec![0; reading.width()];
+            }
+            for (bit, curr) in reading.iter().zip(self.ones_count.iter_mut()) {
+                *curr += if bit { 1 } else { -1 };
+            }
+            Ok(())
+        }
+
+        fn curr_value(&self) -> Power {
+            let gamma: Bits = self.ones_count.iter().map(|v| *v > 0).collect();
+            Power {
+                epsilon: gamma.not(),
+                gamma,
+            }
+        }
+    }
+
+    impl<I, T> Iterator for PowerMeter<I, T>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        type Item = parse::Result<Power>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                let line = self.input.next()?;
+                self.line += 1;
+                if line.as_ref().trim().is_empty() {
+                    continue;
+                }
+                let result = self.process_next_input(line.as_ref());
+                return Some(match result {
+                    Ok(()) => Ok(self.curr_value()),
+                    Err(e) => Err(e.at_line(self.line)),
+                });
+            }
+        }
+    }
+
+    // the rates after the last reading, or the first error on the way
+    pub fn last_power<I, T>(input: I) -> parse::Result<Power>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let mut last = Err(ParseError::new(1, ErrorKind::Empty));
+        for power in PowerMeter::new(input) {
+            last = Ok(power?);
+        }
+        last
+    }
+
+    /// The rates after one reading, with the 1-based columns whose most
+    /// common bit that reading flipped. A tie counts as 0, as in gamma.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Sample {
+        pub reading: usize,
+        pub power: Power,
+        pub consumption: Bits,
+        pub flips: Vec<usize>,
+    }
+
+    impl Sample {
+        pub fn to_csv(&self) -> String {
+            let flips: Vec<String> = self.flips.iter().map(|c| c.to_string()).collect();
+            format!(
+                "{},{},{},{},{}\n",
+                self.reading,
+                self.power.gamma.to_decimal(),
+                self.power.epsilon.to_decimal(),
+                self.consumption.to_decimal(),
+                flips.join(" ")
+            )
+        }
+    }
+
+    impl fmt::Display for Sample {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                f,
+                "reading {}: gamma {}, epsilon {}, power {}",
+                self.reading,
+                self.power.gamma,
+                self.power.epsilon,
+                self.consumption.to_decimal()
+            )?;
+            if !self.flips.is_empty() {
+                let flips: Vec<String> = self.flips.iter().map(|c| c.to_string()).collect();
+                write!(f, ", flipped columns {}", flips.join(", "))?;
+            }
+            Ok(())
+        }
+    }
+
+    // the meter's running output, comparing every gamma with the one before
+    pub fn series<I, T>(input: I) -> impl Iterator<Item = parse::Result<Sample>>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let mut previous: Option<Bits> = None;
+        PowerMeter::new(input).enumerate().map(move |(i, power)| {
+            let power = power?;
+            let flips = match &previous {
+                Some(prev) => prev
+                    .iter()
+                    .zip(power.gamma.iter())
+                    .enumerate()
+                    .filter(|(_, (was, is))| was != is)
+                    .map(|(col, _)| col + 1)
+                    .collect(),
+                None => vec![],
+            };
+            previous = Some(power.gamma.clone());
+            Ok(Sample {
+                reading: i + 1,
+                consumption: power.consumption(),
+                power,
+                flips,
+            })
+        })
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        fn consumption<I, T>(input: I) -> String
+        where
+            I: Iterator<Item = T>,
+            T: AsRef<str>,
+        {
+            last_power(input).unwrap().consumption().to_decimal()
+        }
+
+        #[test]
+        fn puzzle_input() {
+            let lines = [
+                "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+                "11001", "00010", "01010",
+            ]
+            .iter();
+            assert_eq!("198", consumption(lines));
+        }
+
+        #[test]
+        fn two_bits() {
+            let lines = ["00", "01", "11"].iter();
+            assert_eq!("2", consumption(lines));
+        }
+
+        #[test]
+        fn width_from_input() {
+            let power = last_power(["110000000000", "100000000001"].iter()).unwrap();
+            assert_eq!("100000000000", power.gamma.to_string());
+            assert_eq!((2048 * 2047).to_string(), power.consumption().to_decimal());
+
+            let err = last_power(["0110", "", "011"].iter()).unwrap_err();
+            assert_eq!(3, err.line);
+            assert_eq!(
+                ErrorKind::RaggedRow {
+                    expected: 4,
+                    found: 3
+                },
+                err.kind
+            );
+            assert_eq!(3, last_power([" 12"].iter()).unwrap_err().column);
+            assert!(last_power([""].iter()).is_err());
+
+            // two 100 bit readings agreeing on every bit but the last
+            let wide = ["1".repeat(100), format!("{}0", "1".repeat(99))];
+            let power = last_power(wide.iter()).unwrap();
+            assert_eq!("1", power.epsilon.to_decimal());
+            assert_eq!(
+                "1267650600228229401496703205374",
+                power.consumption().to_decimal()
+            );
+        }
+
+        #[test]
+        fn running_output() {
+            let samples: Vec<Sample> = series(["00", "01", "11"].iter())
+                .collect::<parse::Result<_>>()
+                .unwrap();
+            // the second column ties at 01, then tips over to one
+            assert_eq!(
+                vec![vec![], vec![], vec![2]],
+                samples.iter().map(|s| s.flips.clone()).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                "reading 3: gamma 01, epsilon 10, power 2, flipped columns 2",
+                samples[2].to_string()
+            );
+            assert_eq!("2,0,3,0,\n", samples[1].to_csv());
+            assert_eq!("3,1,2,2,2\n", samples[2].to_csv());
+
+            let lines = [
+                "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+                "11001", "00010", "01010",
+            ];
+            let flips: Vec<(usize, Vec<usize>)> = series(lines.iter())
+                .map(|s| s.unwrap())
+                .filter(|s| !s.flips.is_empty())
+                .map(|s| (s.reading, s.flips))
+                .collect();
+            assert_eq!(
+                vec![
+                    (3, vec![1, 4]),
+                    (7, vec![5]),
+                    (8, vec![5]),
+                    (10, vec![4]),
+                    (11, vec![4])
+                ],
+                flips
+            );
+            assert_eq!(
+                "198",
+                series(lines.iter())
+                    .last()
+                    .unwrap()
+                    .unwrap()
+                    .consumption
+                    .to_decimal()
+            );
+        }
+    }
+}
+
+mod part2 {
+    use super::read_bits;
+    use crate::bits::Bits;
+    use crate::parse::{self, ErrorKind, ParseError};
+    use crate::trie::BinaryTrie;
+
+    pub fn solve<I, T>(lines: I) -> parse::Result<Bits>
+    where
+        I: Iterator<Item = T>,
+        T: AsRef<str>,
+    {
+        // sized by the first reading
+        let mut trie: Option<BinaryTrie> = None;
+        for (i, line) in lines.enumerate() {
+            let line = line.as_ref();
+            if line.trim().is_empty() {
+                continue;
+            }
+            let reading =
+                read_bits(line, trie.as_ref().map(|t| t.width())).map_err(|e| e.at_line(i + 1))?;
+            trie.get_or_insert_with(|| BinaryTrie::new(reading.width()))
+                .insert(&reading);
+        }
+        let trie = trie.ok_or_else(|| ParseError::new(1, ErrorKind::Empty))?;
+
+        // keep the most common bit, ones on a tie, then the least common
+        let o2_gen = trie.descend(|zeros, ones| ones >= zeros).unwrap();
+        let co2_scrub = trie.descend(|zeros, ones| ones < zeros).unwrap();
+
+        println!(
+            "o2: {}, co2: {}",
+            o2_gen.to_decimal(),
+            co2_scrub.to_decimal()
+        );
+        Ok(o2_gen.mul(&co2_scrub))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn simple_input() {
+            let lines = ["111", "010"].iter();
+            assert_eq!("14", solve(lines).unwrap().to_decimal());
+        }
+
+        #[test]
+        fn puzzle_input() {
+            let lines = [
+                "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+                "11001", "00010", "01010",
+            ]
+            .iter();
+            assert_eq!("230", solve(lines).unwrap().to_decimal());
+        }
+
+        #[test]
+        fn repeated_readings() {
+            // duplicates count, the two 0110 outvote 0111 for oxygen
+            let lines = ["0110", "0110", "1000", "1001", "0111"].iter();
+            assert_eq!((6 * 8).to_string(), solve(lines).unwrap().to_decimal());
+        }
+
+        #[test]
+        fn bad_readings() {
+            let err = solve(["0110", "01x0"].iter()).unwrap_err();
+            assert_eq!((2, 3), (err.line, err.column));
+            let err = solve(["0110", "011"].iter()).unwrap_err();
+            assert_eq!(2, err.line);
+            assert!(matches!(err.kind, ErrorKind::RaggedRow { .. }));
+            assert_eq!(ErrorKind::Empty, solve(["", " "].iter()).unwrap_err().kind);
+        }
+    }
+}
+
+// appends `row` to the open csv file, if there is one. A failed write
+// closes it, the series still prints.
+fn write_row(csv: &mut Option<(&str, BufWriter<File>)>, row: &str) {
+    let failed = match csv {
+        Some((path, out)) => out.write_all(row.as_bytes()).err().map(|e| (*path, e)),
+        None => None,
+    };
+    if let Some((path, e)) = failed {
+        println!("series not saved, cannot write {}: {}", path, e);
+        *csv = None;
+    }
+}
+
+// the part 1 answer, printing every sample on the way and saving them all
+// as CSV to `path` when asked
+fn power_series<I>(lines: I, print: bool, path: Option<&str>) -> parse::Result<part1::Power>
+where
+    I: Iterator<Item = String>,
+{
+    // rows go out as the readings come in
+    let mut csv = path.and_then(|path| match File::create(path) {
+        Ok(file) => Some((path, BufWriter::new(file))),
+        Err(e) => {
+            println!("series not saved, cannot write {}: {}", path, e);
+            None
+        }
+    });
+    write_row(&mut csv, "reading,gamma,epsilon,power,flipped\n");
+    let mut last = None;
+    for sample in part1::series(lines) {
+        let sample = sample?;
+        if print {
+            println!("{}", sample);
+        }
+        write_row(&mut csv, &sample.to_csv());
+        last = Some(sample.power);
+    }
+    let power = last.ok_or_else(|| ParseError::new(1, ErrorKind::Empty))?;
+    if let Some((path, mut out)) = csv {
+        match out.flush() {
+            Ok(()) => println!("saved {}", path),
+            Err(e) => println!("series not saved, cannot write {}: {}", path, e),
+        }
+    }
+    Ok(power)
+}
+
+/// How much part 1 reports besides the answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Report {
+    /// The rates after every reading.
+    Series,
+}
+
+impl FromStr for Report {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> parse::Result<Self> {
+        match s {
+            "series" => Ok(Report::Series),
+            _ => Err(ParseError::new(
+                1,
+                ErrorKind::Unexpected {
+                    found: s.to_string(),
+                    expected: "series".to_string(),
+                },
+            )),
+        }
+    }
+}
+
+// `-P report=series` lists gamma, epsilon and the power after every reading
+// of part 1, and the columns whose most common bit flipped. `-P
+// series=path.csv` saves the same.
+pub fn solve<I>(lines: I, part: u8, params: &Params) -> parse::Result<()>
+where
+    I: Iterator<Item = String>,
+{
+    match part {
+        1 => {
+            let print = params.get::<Report>("report")? == Some(Report::Series);
+            let path: Option<String> = params.get("series")?;
+            let power = if print || path.is_some() {
+                power_series(lines, print, path.as_deref())?
+            } else {
+                part1::last_power(lines)?
+            };
+            println!("part1: {}", power.consumption().to_decimal());
+        }
+        2 => println!("part2: {}", part2::solve(lines)?.to_decimal()),
+        _ => {}
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use crate::parse::{self, ErrorKind, ParseError, Section};
+
 struct BingoBoard<const N: usize> {
     board: Vec<Vec<(u32, bool)>>,
     row_marked: Vec<u32>,
@@ -6,25 +8,35 @@ struct BingoBoard<const N: usize> {
 }
 
 impl<const N: usize> BingoBoard<N> {
-    pub fn from_lines(lines: &[String]) -> Self {
-        let mut board = Vec::new();
-
-        for line in lines {
-            let row: Vec<(u32, bool)> = line
-                .trim()
-                .split_whitespace()
-                .map(|num| (num.trim().parse::<u32>().unwrap(), false))
-                .collect();
-            assert!(row.len() == N);
-            board.push(row);
+    pub fn from_section(section: &Section) -> parse::Result<Self> {
+        let rows = section.parse_lines(|l| {
+            let row = parse::number_fields::<u32>(l)?;
+            if row.len() != N {
+                let kind = ErrorKind::RaggedRow {
+                    expected: N,
+                    found: row.len(),
+                };
+                return Err(ParseError::new(1, kind));
+            }
+            Ok(row)
+        })?;
+        if rows.len() != N {
+            let kind = ErrorKind::Unexpected {
+                found: format!("{} rows", rows.len()),
+                expected: format!("{} rows", N),
+            };
+            return Err(ParseError::new(1, kind).at_line(section.first_line));
         }
-        assert!(board.len() == N);
-        BingoBoard {
+        let board = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(|num| (num, false)).collect())
+            .collect();
+        Ok(BingoBoard {
             board,
             row_marked: vec![0; N],
             col_marked: vec![0; N],
             has_won: false,
-        }
+        })
     }
 
     pub fn mark_num(&mut self, num: u32) -> bool {
@@ -59,44 +71,34 @@ impl<const N: usize> BingoBoard<N> {
     }
 }
 
-fn drawn_numbers(num_str: &str) -> Vec<u32> {
-    num_str
-        .trim()
-        .split(',')
-        .map(|num| num.trim().parse::<u32>().unwrap())
-        .collect()
-}
-
-fn create_boards<I, T>(lines: I) -> Vec<BingoBoard<5>>
+fn parse_game<I, T>(lines: I) -> parse::Result<(Vec<u32>, Vec<BingoBoard<5>>)>
 where
     I: Iterator<Item = T>,
     T: AsRef<str>,
 {
-    let mut boards: Vec<BingoBoard<5>> = Vec::new();
-
-    let mut board_lines = Vec::new();
-    for l in lines {
-        board_lines.push(l.as_ref().to_string());
-        if board_lines.len() == 5 {
-            boards.push(BingoBoard::from_lines(&board_lines));
-            board_lines.clear();
-        }
-    }
-    boards
+    let sections = parse::sections(lines);
+    let (seq, boards) = sections
+        .split_first()
+        .ok_or(ParseError::new(1, ErrorKind::Empty))?;
+
+    let seq = seq
+        .parse_lines(|l| parse::number_list::<u32>(l, ','))?
+        .concat();
+    let boards = boards
+        .iter()
+        .map(BingoBoard::from_section)
+        .collect::<parse::Result<_>>()?;
+    Ok((seq, boards))
 }
 
 mod part1 {
     use super::*;
-    pub fn solve<I, T>(lines: I) -> u32
+    pub fn solve<I, T>(lines: I) -> parse::Result<u32>
     where
         I: Iterator<Item = T>,
         T: AsRef<str>,
     {
-        let mut lines = lines.filter(|l| !l.as_ref().is_empty());
-
-        let seq = drawn_numbers(lines.next().unwrap().as_ref());
-
-        let mut boards = create_boards(lines);
+        let (seq, mut boards) = parse_game(lines)?;
 
         for num in seq {
             if let Some(winner) =
@@ -104,27 +106,23 @@ mod part1 {
                     .iter_mut()
                     .find_map(|b| if b.mark_num(num) { Some(b) } else { None })
             {
-                return winner.get_score(num);
+                return Ok(winner.get_score(num));
             }
         }
 
         // no winner
-        0
+        Ok(0)
     }
 }
 
 mod part2 {
     use super::*;
-    pub fn solve<I, T>(lines: I) -> u32
+    pub fn solve<I, T>(lines: I) -> parse::Result<u32>
     where
         I: Iterator<Item = T>,
         T: AsRef<str>,
     {
-        let mut lines = lines.filter(|l| !l.as_ref().is_empty());
-
-        let seq = drawn_numbers(lines.next().unwrap().as_ref());
-
-        let mut boards = create_boards(lines);
+        let (seq, mut boards) = parse_game(lines)?;
         let mut last_winner = (0, 0);
 
         for num in seq {
@@ -134,7 +132,7 @@ mod part2 {
                 }
             }
         }
-        boards[last_winner.0].get_score(last_winner.1)
+        Ok(boards[last_winner.0].get_score(last_winner.1))
     }
 }
 
@@ -165,18 +163,29 @@ mod test {
 22 11 13  6  5
  2  0 12  3  7
 ";
-        assert_eq!(4512, part1::solve(input.lines()));
-        assert_eq!(1924, part2::solve(input.lines()));
+        assert_eq!(Ok(4512), part1::solve(input.lines()));
+        assert_eq!(Ok(1924), part2::solve(input.lines()));
+    }
+
+    #[test]
+    fn bad_boards() {
+        let short_row = "7,4\n\n1 2 3 4 5\n1 2 3 4\n";
+        let err = part1::solve(short_row.lines()).unwrap_err();
+        assert_eq!((4, 1), (err.line, err.column));
+        let short_board = "7,4\n\n1 2 3 4 5\n";
+        assert_eq!(3, part1::solve(short_board.lines()).unwrap_err().line);
+        assert!(part1::solve("".lines()).is_err());
     }
 }
 
-pub fn solve<I>(lines: I, part: u8)
+pub fn solve<I>(lines: I, part: u8) -> parse::Result<()>
 where
     I: Iterator<Item = String>,
 {
     match part {
-        1 => println!("part1: {}", part1::solve(lines)),
-        2 => println!("part2: {}", part2::solve(lines)),
+        1 => println!("part1: {}", part1::solve(lines)?),
+        2 => println!("part2: {}", part2::solve(lines)?),
         _ => {}
     }
+    Ok(())
 }
@@ -1,3 +1,5 @@
+use crate::parse;
+use crate::visual::{self, Cell, Frame, FrameSink};
 use std::cmp;
 use std::collections::HashMap;
 use std::ops::RangeInclusive;
@@ -20,26 +22,10 @@ struct Segment {
 }
 
 impl Segment {
-    pub fn new(line: &str) -> Self {
-        let points: Vec<Point> = line
-            .trim()
-            .split("->")
-            .map(|p| {
-                let ends: Vec<i32> = p
-                    .trim()
-                    .split(',')
-                    .map(|e| e.parse::<i32>().unwrap())
-                    .collect();
-                Point {
-                    x: ends[0],
-                    y: ends[1],
-                }
-            })
-            .collect();
-        Segment {
-            point1: points[0].clone(),
-            point2: points[1].clone(),
-        }
+    pub fn parse(line: &str) -> parse::Result<Self> {
+        let point = |p| parse::number_pair(p, ",").map(|(x, y)| Point::new(x, y));
+        let (point1, point2) = parse::separated_pair(line, "->", point, point)?;
+        Ok(Segment { point1, point2 })
     }
 
     pub fn is_horizontal(&self) -> bool {
@@ -105,14 +91,13 @@ where
     }
 }
 
-pub fn solve_impl<I, T>(lines: I, no_diag: bool) -> usize
+fn vent_map<I, T>(lines: I, no_diag: bool) -> parse::Result<HashMap<Point, usize>>
 where
     I: Iterator<Item = T>,
     T: AsRef<str>,
 {
-    let segments: Vec<Segment> = lines
-        .filter(|l| !l.as_ref().is_empty())
-        .map(|l| Segment::new(l.as_ref()))
+    let segments: Vec<Segment> = parse::lines_with(lines, Segment::parse)?
+        .into_iter()
         .filter(|s| !no_diag || s.is_horizontal() || s.is_vertical())
         .collect();
 
@@ -123,17 +108,52 @@ where
             *count += 1;
         }
     }
+    Ok(grid)
+}
 
-    grid.values().filter(|&v| *v >= 2).count()
+pub fn solve_impl<I, T>(lines: I, no_diag: bool) -> parse::Result<usize>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    Ok(vent_map(lines, no_diag)?
+        .values()
+        .filter(|&v| *v >= 2)
+        .count())
+}
+
+fn density_frame(grid: &HashMap<Point, usize>) -> Frame {
+    let max_x = grid.keys().map(|p| p.x).max().unwrap_or(0).max(0) as usize;
+    let max_y = grid.keys().map(|p| p.y).max().unwrap_or(0).max(0) as usize;
+    let max_count = *grid.values().max().unwrap_or(&0) as u32;
+    let overlaps = grid.values().filter(|&v| *v >= 2).count();
+
+    let mut frame =
+        Frame::new(max_x + 1, max_y + 1).with_caption(format!("overlapping points: {}", overlaps));
+    for x in 0..=max_x {
+        for y in 0..=max_y {
+            frame.set(x, y, Cell::new('.', visual::GREY));
+        }
+    }
+    for (point, &count) in grid {
+        if point.x < 0 || point.y < 0 {
+            continue;
+        }
+        let glyph = std::char::from_digit(count.min(9) as u32, 10).unwrap();
+        let cell = Cell::new(
+            glyph,
+            visual::heat(count as u32 - 1, max_count.saturating_sub(1)),
+        );
+        frame.set(point.x as usize, point.y as usize, cell);
+    }
+    frame
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[test]
-    fn puzzle_input() {
-        let input = "
+    const INPUT: &str = "
 0,9 -> 5,9
 8,0 -> 0,8
 9,4 -> 3,4
@@ -145,18 +165,40 @@ mod test {
 0,0 -> 8,8
 5,5 -> 8,2
 ";
-        assert_eq!(5, solve_impl(input.lines(), true));
-        assert_eq!(12, solve_impl(input.lines(), false));
+
+    #[test]
+    fn puzzle_input() {
+        assert_eq!(Ok(5), solve_impl(INPUT.lines(), true));
+        assert_eq!(Ok(12), solve_impl(INPUT.lines(), false));
+    }
+
+    #[test]
+    fn density_visualisation() {
+        let frame = density_frame(&vent_map(INPUT.lines(), false).unwrap());
+        assert_eq!((10, 10), (frame.width(), frame.height()));
+        assert_eq!('2', frame.get(0, 9).glyph);
+        assert_eq!('.', frame.get(9, 9).glyph);
+        assert_eq!("overlapping points: 12", frame.caption);
     }
 }
 
-pub fn solve<I>(lines: I, part: u8)
+pub fn visualize<I>(lines: I, part: u8, sink: &mut dyn FrameSink) -> parse::Result<()>
+where
+    I: Iterator<Item = String>,
+{
+    let grid = vent_map(lines, part == 1)?;
+    sink.draw(&density_frame(&grid));
+    Ok(())
+}
+
+pub fn solve<I>(lines: I, part: u8) -> parse::Result<()>
 where
     I: Iterator<Item = String>,
 {
     match part {
-        1 => println!("part1: {}", solve_impl(lines, true)),
-        2 => println!("part2: {}", solve_impl(lines, false)),
+        1 => println!("part1: {}", solve_impl(lines, true)?),
+        2 => println!("part2: {}", solve_impl(lines, false)?),
         _ => {}
     }
+    Ok(())
 }
@@ -0,0 +1,52 @@
+use crate::parse;
+
+pub fn solve_impl<I, T>(lines: I, days: u32) -> parse::Result<usize>
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    let mut fishes: Vec<(u64, u64)> =
+        parse::first_line_with(lines, |l| parse::number_list(l, ','))?
+            .into_iter()
+            .map(|n| (1, n))
+            .collect();
+
+    for _ in 0..days {
+        let mut new_fishes = 0;
+        for (sibs, counter) in &mut fishes {
+            if *counter == 0 {
+                *counter = 6;
+                new_fishes += *sibs;
+            } else {
+                *counter -= 1;
+            }
+        }
+        fishes.push((new_fishes, 8));
+    }
+
+    Ok(fishes.iter().fold(0, |acc, (sibs, _)| acc + *sibs as usize))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn puzzle_input() {
+        let input = "3,4,3,1,2";
+        assert_eq!(Ok(26984457539), solve_impl(input.lines(), 256));
+        assert_eq!(1, solve_impl("".lines(), 80).unwrap_err().line);
+    }
+}
+
+pub fn solve<I>(lines: I, part: u8) -> parse::Result<()>
+where
+    I: Iterator<Item = String>,
+{
+    match part {
+        1 => println!("part1: {}", solve_impl(lines, 80)?),
+        2 => println!("part2: {}", solve_impl(lines, 256)?),
+        _ => {}
+    }
+    Ok(())
+}
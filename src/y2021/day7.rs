@@ -1,17 +1,15 @@
+use crate::parse;
+
 mod part1 {
-    pub fn solve<I, T>(mut lines: I) -> u32
+    use crate::parse;
+
+    pub fn solve<I, T>(lines: I) -> parse::Result<u32>
     where
         I: Iterator<Item = T>,
         T: AsRef<str>,
     {
-        let mut positions: Vec<u32> = lines
-            .next()
-            .unwrap()
-            .as_ref()
-            .trim()
-            .split(',')
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect();
+        let mut positions: Vec<u32> =
+            parse::first_line_with(lines, |l| parse::number_list(l, ','))?;
 
         // sort positions
         positions.sort_unstable();
@@ -47,34 +45,29 @@ mod part1 {
             crabs_to_move += 1;
         }
 
-        let mut result = u32::max_value();
+        let mut result = u32::MAX;
         for i in 0..positions.len() {
             result = std::cmp::min(result, left_move_cost[i] + right_move_cost[i]);
         }
 
-        result
+        Ok(result)
     }
 }
 
 mod part2 {
-    pub fn solve<I, T>(mut lines: I) -> i32
+    use crate::parse;
+
+    pub fn solve<I, T>(lines: I) -> parse::Result<i32>
     where
         I: Iterator<Item = T>,
         T: AsRef<str>,
     {
-        let positions: Vec<i32> = lines
-            .next()
-            .unwrap()
-            .as_ref()
-            .trim()
-            .split(',')
-            .map(|n| n.parse::<i32>().unwrap())
-            .collect();
+        let positions: Vec<i32> = parse::first_line_with(lines, |l| parse::number_list(l, ','))?;
 
         let min = *positions.iter().min().unwrap_or(&0);
         let max = *positions.iter().max().unwrap_or(&0);
 
-        (min..=max)
+        Ok((min..=max)
             .map(|i| {
                 positions
                     .iter()
@@ -85,7 +78,7 @@ mod part2 {
                     .sum()
             })
             .min()
-            .unwrap()
+            .unwrap())
     }
 }
 
@@ -96,18 +89,20 @@ mod test {
     #[test]
     fn puzzle_input() {
         let input = "16,1,2,0,4,2,7,1,2,14";
-        assert_eq!(37, part1::solve(input.lines()));
-        assert_eq!(168, part2::solve(input.lines()));
+        assert_eq!(Ok(37), part1::solve(input.lines()));
+        assert_eq!(Ok(168), part2::solve(input.lines()));
+        assert_eq!(5, part1::solve("1,2,x".lines()).unwrap_err().column);
     }
 }
 
-pub fn solve<I>(lines: I, part: u8)
+pub fn solve<I>(lines: I, part: u8) -> parse::Result<()>
 where
     I: Iterator<Item = String>,
 {
     match part {
-        1 => println!("part1: {}", part1::solve(lines)),
-        2 => println!("part2: {}", part2::solve(lines)),
+        1 => println!("part1: {}", part1::solve(lines)?),
+        2 => println!("part2: {}", part2::solve(lines)?),
         _ => {}
     }
+    Ok(())
 }
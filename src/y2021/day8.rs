@@ -1,3 +1,4 @@
+use crate::parse;
 use std::collections::HashSet;
 
 fn part1(entry: &str) -> u32 {
@@ -169,7 +170,7 @@ be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbg
     }
 }
 
-pub fn solve<I>(lines: I, part: u8)
+pub fn solve<I>(lines: I, part: u8) -> parse::Result<()>
 where
     I: Iterator<Item = String>,
 {
@@ -178,4 +179,5 @@ where
         2 => println!("part2: {}", solve_impl(lines, 2)),
         _ => {}
     }
+    Ok(())
 }
@@ -1,3 +1,5 @@
+use crate::parse;
+use crate::visual::{self, Cell, Frame, FrameSink};
 use std::collections::VecDeque;
 
 #[derive(Debug)]
@@ -18,23 +20,13 @@ impl Location {
 }
 
 impl HeightMap {
-    pub fn from_lines<I, T>(lines: I) -> Self
+    pub fn from_lines<I, T>(lines: I) -> parse::Result<Self>
     where
         I: Iterator<Item = T>,
         T: AsRef<str>,
     {
-        let mut map = vec![];
-        for line in lines {
-            let trim_line = line.as_ref().trim();
-            if !trim_line.is_empty() {
-                let row: Vec<u32> = trim_line
-                    .chars()
-                    .map(|c| c.to_digit(10).expect("map value must be between 0-9"))
-                    .collect();
-                map.push(row);
-            }
-        }
-        HeightMap { map }
+        let map = parse::digit_grid(lines)?;
+        Ok(HeightMap { map })
     }
 
     pub fn up(&self, loc: &Location) -> Option<(Location, u32)> {
@@ -86,7 +78,7 @@ impl HeightMap {
     }
 
     pub fn col_len(&self) -> usize {
-        if let Some(row) = self.map.get(0) {
+        if let Some(row) = self.map.first() {
             row.len()
         } else {
             0
@@ -123,24 +115,24 @@ impl HeightMap {
 const VISITED: u32 = 10;
 const PEAK: u32 = 9;
 
-fn bfs(hm: &mut HeightMap, loc: &Location) -> usize {
+fn bfs(hm: &mut HeightMap, loc: &Location) -> Vec<Location> {
     hm.set(loc, VISITED);
     let mut bfs_que = VecDeque::from([loc.clone()]);
-    let mut count = 1;
+    let mut basin = vec![loc.clone()];
     while let Some(curr_loc) = bfs_que.pop_front() {
         for next in hm.neighbours(&curr_loc) {
             let (next_loc, next_elem) = next;
             if next_elem != VISITED && next_elem != PEAK {
                 hm.set(&next_loc, VISITED);
-                bfs_que.push_back(next_loc);
-                count += 1;
+                bfs_que.push_back(next_loc.clone());
+                basin.push(next_loc);
             }
         }
     }
-    count
+    basin
 }
 
-fn basins(hm: &mut HeightMap) -> Vec<usize> {
+fn basin_cells(hm: &mut HeightMap) -> Vec<Vec<Location>> {
     let mut result = vec![];
     for r in 0..hm.row_len() {
         for c in 0..hm.col_len() {
@@ -153,6 +145,11 @@ fn basins(hm: &mut HeightMap) -> Vec<usize> {
             }
         }
     }
+    result
+}
+
+fn basins(hm: &mut HeightMap) -> Vec<usize> {
+    let mut result: Vec<usize> = basin_cells(hm).iter().map(|b| b.len()).collect();
     result.sort_unstable();
     result
 }
@@ -161,18 +158,18 @@ fn part2(hm: &mut HeightMap) -> usize {
     basins(hm).iter().rev().take(3).product()
 }
 
+fn is_low_point(hm: &HeightMap, loc: &Location) -> bool {
+    let curr = hm.map[loc.row][loc.col];
+    hm.neighbours(loc).iter().all(|n| curr < n.1)
+}
+
 fn part1(hm: &HeightMap) -> u32 {
     let mut result = 0;
     for r in 0..hm.row_len() {
         result += (0..hm.col_len())
             .map(|c| {
-                let curr = hm.map[r][c];
-                if hm
-                    .neighbours(&Location::new(r, c))
-                    .iter()
-                    .all(|n| curr < n.1)
-                {
-                    1 + curr
+                if is_low_point(hm, &Location::new(r, c)) {
+                    1 + hm.map[r][c]
                 } else {
                     0
                 }
@@ -182,6 +179,52 @@ fn part1(hm: &HeightMap) -> u32 {
     result
 }
 
+fn height_frame(hm: &HeightMap) -> Frame {
+    let mut frame = Frame::new(hm.col_len(), hm.row_len());
+    for r in 0..hm.row_len() {
+        for c in 0..hm.col_len() {
+            let height = hm.map[r][c];
+            let glyph = std::char::from_digit(height, 10).unwrap();
+            let cell = if height == PEAK {
+                Cell::new(glyph, visual::GREY)
+            } else if is_low_point(hm, &Location::new(r, c)) {
+                Cell::new(glyph, visual::BLACK).on(visual::YELLOW)
+            } else {
+                Cell::new(glyph, visual::heat(height, PEAK))
+            };
+            frame.set(c, r, cell);
+        }
+    }
+    frame
+}
+
+fn basin_frame(hm: &HeightMap) -> Frame {
+    let mut frame = height_frame(hm);
+    let mut scratch = HeightMap {
+        map: hm.map.clone(),
+    };
+    let mut basins = basin_cells(&mut scratch);
+    basins.sort_by_key(|b| std::cmp::Reverse(b.len()));
+
+    for (i, basin) in basins.iter().enumerate() {
+        for loc in basin {
+            let glyph = frame.get(loc.col, loc.row).glyph;
+            let cell = if i < 3 {
+                Cell::new(glyph, visual::BLACK).on(visual::label(i))
+            } else {
+                Cell::new(glyph, visual::label(i))
+            };
+            frame.set(loc.col, loc.row, cell);
+        }
+    }
+    let largest: usize = basins.iter().take(3).map(|b| b.len()).product();
+    frame.with_caption(format!(
+        "{} basins, product of three largest: {}",
+        basins.len(),
+        largest
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -195,20 +238,38 @@ mod test {
 8767896789
 9899965678
 ";
-        let mut hm = HeightMap::from_lines(input.lines());
+        let mut hm = HeightMap::from_lines(input.lines()).unwrap();
         assert_eq!(15, part1(&hm));
+        assert_eq!(
+            "4 basins, product of three largest: 1134",
+            basin_frame(&hm).caption
+        );
         assert_eq!(1134, part2(&mut hm));
     }
 }
 
-pub fn solve<I>(lines: I, part: u8)
+pub fn visualize<I>(lines: I, part: u8, sink: &mut dyn FrameSink) -> parse::Result<()>
+where
+    I: Iterator<Item = String>,
+{
+    let hm = HeightMap::from_lines(lines)?;
+    match part {
+        1 => sink.draw(&height_frame(&hm).with_caption(format!("risk level: {}", part1(&hm)))),
+        2 => sink.draw(&basin_frame(&hm)),
+        _ => {}
+    }
+    Ok(())
+}
+
+pub fn solve<I>(lines: I, part: u8) -> parse::Result<()>
 where
     I: Iterator<Item = String>,
 {
-    let mut hm = HeightMap::from_lines(lines);
+    let mut hm = HeightMap::from_lines(lines)?;
     match part {
         1 => println!("part1: {}", part1(&hm)),
         2 => println!("part2: {}", part2(&mut hm)),
         _ => {}
     }
+    Ok(())
 }
@@ -0,0 +1,232 @@
+use crate::registry::Solver;
+
+#[cfg(feature = "y2021-day1")]
+mod day1;
+#[cfg(feature = "y2021-day10")]
+mod day10;
+#[cfg(feature = "y2021-day11")]
+mod day11;
+#[cfg(feature = "y2021-day12")]
+mod day12;
+#[cfg(feature = "y2021-day13")]
+mod day13;
+#[cfg(feature = "y2021-day14")]
+mod day14;
+#[cfg(feature = "y2021-day15")]
+mod day15;
+#[cfg(feature = "y2021-day16")]
+mod day16;
+#[cfg(feature = "y2021-day17")]
+mod day17;
+#[cfg(feature = "y2021-day18")]
+mod day18;
+#[cfg(feature = "y2021-day19")]
+mod day19;
+#[cfg(feature = "y2021-day2")]
+mod day2;
+#[cfg(feature = "y2021-day20")]
+mod day20;
+#[cfg(feature = "y2021-day21")]
+mod day21;
+#[cfg(feature = "y2021-day22")]
+mod day22;
+#[cfg(feature = "y2021-day23")]
+mod day23;
+#[cfg(feature = "y2021-day24")]
+mod day24;
+#[cfg(feature = "y2021-day25")]
+mod day25;
+#[cfg(feature = "y2021-day3")]
+mod day3;
+#[cfg(feature = "y2021-day4")]
+mod day4;
+#[cfg(feature = "y2021-day5")]
+mod day5;
+#[cfg(feature = "y2021-day6")]
+mod day6;
+#[cfg(feature = "y2021-day7")]
+mod day7;
+#[cfg(feature = "y2021-day8")]
+mod day8;
+#[cfg(feature = "y2021-day9")]
+mod day9;
+
+// one entry per day module compiled into this build, see the `y2021-dayN`
+// features in Cargo.toml.
+pub const SOLVERS: &[Solver] = &[
+    #[cfg(feature = "y2021-day1")]
+    Solver {
+        year: 2021,
+        day: 1,
+        solve: day1::solve,
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day2")]
+    Solver {
+        year: 2021,
+        day: 2,
+        solve: day2::solve,
+        visualize: Some(day2::visualize),
+    },
+    #[cfg(feature = "y2021-day3")]
+    Solver {
+        year: 2021,
+        day: 3,
+        solve: day3::solve,
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day4")]
+    Solver {
+        year: 2021,
+        day: 4,
+        solve: |lines, part, _| day4::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day5")]
+    Solver {
+        year: 2021,
+        day: 5,
+        solve: |lines, part, _| day5::solve(lines, part),
+        visualize: Some(|lines, part, _, sink| day5::visualize(lines, part, sink)),
+    },
+    #[cfg(feature = "y2021-day6")]
+    Solver {
+        year: 2021,
+        day: 6,
+        solve: |lines, part, _| day6::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day7")]
+    Solver {
+        year: 2021,
+        day: 7,
+        solve: |lines, part, _| day7::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day8")]
+    Solver {
+        year: 2021,
+        day: 8,
+        solve: |lines, part, _| day8::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day9")]
+    Solver {
+        year: 2021,
+        day: 9,
+        solve: |lines, part, _| day9::solve(lines, part),
+        visualize: Some(|lines, part, _, sink| day9::visualize(lines, part, sink)),
+    },
+    #[cfg(feature = "y2021-day10")]
+    Solver {
+        year: 2021,
+        day: 10,
+        solve: |lines, part, _| day10::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day11")]
+    Solver {
+        year: 2021,
+        day: 11,
+        solve: |lines, part, _| day11::solve(lines, part),
+        visualize: Some(|lines, part, _, sink| day11::visualize(lines, part, sink)),
+    },
+    #[cfg(feature = "y2021-day12")]
+    Solver {
+        year: 2021,
+        day: 12,
+        solve: |lines, part, _| day12::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day13")]
+    Solver {
+        year: 2021,
+        day: 13,
+        solve: |lines, part, _| day13::solve(lines, part),
+        visualize: Some(|lines, part, _, sink| day13::visualize(lines, part, sink)),
+    },
+    #[cfg(feature = "y2021-day14")]
+    Solver {
+        year: 2021,
+        day: 14,
+        solve: day14::solve,
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day15")]
+    Solver {
+        year: 2021,
+        day: 15,
+        solve: |lines, part, _| day15::solve(lines, part),
+        visualize: Some(|lines, part, _, sink| day15::visualize(lines, part, sink)),
+    },
+    #[cfg(feature = "y2021-day16")]
+    Solver {
+        year: 2021,
+        day: 16,
+        solve: |lines, part, _| day16::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day17")]
+    Solver {
+        year: 2021,
+        day: 17,
+        solve: |lines, part, _| day17::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day18")]
+    Solver {
+        year: 2021,
+        day: 18,
+        solve: |lines, part, _| day18::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day19")]
+    Solver {
+        year: 2021,
+        day: 19,
+        solve: |lines, part, _| day19::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day20")]
+    Solver {
+        year: 2021,
+        day: 20,
+        solve: day20::solve,
+        visualize: Some(day20::visualize),
+    },
+    #[cfg(feature = "y2021-day21")]
+    Solver {
+        year: 2021,
+        day: 21,
+        solve: day21::solve,
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day22")]
+    Solver {
+        year: 2021,
+        day: 22,
+        solve: |lines, part, _| day22::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day23")]
+    Solver {
+        year: 2021,
+        day: 23,
+        solve: day23::solve,
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day24")]
+    Solver {
+        year: 2021,
+        day: 24,
+        solve: |lines, part, _| day24::solve(lines, part),
+        visualize: None,
+    },
+    #[cfg(feature = "y2021-day25")]
+    Solver {
+        year: 2021,
+        day: 25,
+        solve: |lines, part, _| day25::solve(lines, part),
+        visualize: Some(|lines, part, _, sink| day25::visualize(lines, part, sink)),
+    },
+];